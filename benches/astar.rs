@@ -0,0 +1,109 @@
+//! Compares the frontiers of `utils::astar`. Run with `cargo +nightly bench`.
+#![feature(test)]
+extern crate test;
+
+use aoc2023rust::{
+    utils::astar::{
+        search_with, BTreeFrontier, BucketFrontier, Frontier, HeapFrontier, SearchState,
+    },
+    y2023::day17,
+    Solver,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use test::Bencher;
+
+const SIZE: usize = 141;
+
+/// A grid of heat losses like the day 17 puzzle input.
+fn heat_map() -> String {
+    let mut rng = ChaCha8Rng::seed_from_u64(17);
+    (0..SIZE)
+        .map(|_| {
+            (0..SIZE)
+                .map(|_| char::from(b'0' + rng.gen_range(1..=9)))
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
+
+/// Walks from the top left to the bottom right corner of the grid.
+struct Walk<'a> {
+    grid: &'a [u8],
+    pos: (usize, usize),
+    cost: usize,
+}
+
+impl SearchState for Walk<'_> {
+    type Key = (usize, usize);
+    type Iter = std::vec::IntoIter<Self>;
+
+    fn key(&self) -> Self::Key {
+        self.pos
+    }
+
+    fn is_goal(&self) -> bool {
+        self.pos == (SIZE - 1, SIZE - 1)
+    }
+
+    fn cost(&self) -> usize {
+        self.cost
+    }
+
+    fn heuristic(&self) -> usize {
+        (SIZE - 1 - self.pos.0) + (SIZE - 1 - self.pos.1)
+    }
+
+    fn next_states(self) -> Self::Iter {
+        let (row, col) = self.pos;
+        [
+            (row.wrapping_sub(1), col),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+            (row, col + 1),
+        ]
+        .into_iter()
+        .filter(|&(row, col)| row < SIZE && col < SIZE)
+        .map(|pos| Walk {
+            grid: self.grid,
+            pos,
+            cost: self.cost + (self.grid[pos.0 * (SIZE + 1) + pos.1] - b'0') as usize,
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+    }
+}
+
+fn bench_walk<'a, F: Frontier<Walk<'a>>>(b: &mut Bencher, grid: &'a str) {
+    b.iter(|| {
+        search_with::<_, F>(Walk {
+            grid: grid.as_bytes(),
+            pos: (0, 0),
+            cost: 0,
+        })
+        .goal
+        .map(|goal| goal.cost)
+    });
+}
+
+#[bench]
+fn walk_btree(b: &mut Bencher) {
+    bench_walk::<BTreeFrontier<_>>(b, &heat_map());
+}
+
+#[bench]
+fn walk_heap(b: &mut Bencher) {
+    bench_walk::<HeapFrontier<_>>(b, &heat_map());
+}
+
+#[bench]
+fn walk_bucket(b: &mut Bencher) {
+    bench_walk::<BucketFrontier<_>>(b, &heat_map());
+}
+
+#[bench]
+fn day17(b: &mut Bencher) {
+    let input = heat_map();
+    b.iter(|| day17::Solution.solve(&input).unwrap());
+}
@@ -1,74 +1,554 @@
-use std::fs;
-
-use reqwest::header;
-
-pub fn maybe_fetch_puzzle_data(year: u32, day: u32) -> String {
-    let cookie = get_cookie();
-    let cache_dir = dirs::cache_dir()
-        .unwrap()
-        .join("aoc-data")
-        .join(format!("{year}"));
-    fs::create_dir_all(&cache_dir).ok();
-    let puzzle_input_file = cache_dir.join(format!("input{day}.txt"));
-    match puzzle_input_file.try_exists() {
-        Ok(true) => fs::read_to_string(puzzle_input_file).unwrap(),
-        Ok(false) => {
-            let url = format!("https://adventofcode.com/{year}/day/{day}/input");
-            let cookieheader = format!("session={cookie}");
-            println!("\u{1f385} Fetching input data for {year} day {day}");
-            let contents = reqwest::blocking::Client::new()
-                .get(url)
-                .header(header::COOKIE, cookieheader.trim())
-                .send()
-                .unwrap()
-                .text()
-                .unwrap();
-            fs::write(puzzle_input_file, &contents).unwrap();
-            contents
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::{blocking::Client, header};
+
+use crate::{puzzle_descr, utils};
+
+const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (Advent of Code puzzle runner)"
+);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a fetched puzzle description is used before it may be refreshed.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Where puzzle data is cached, and whether it may be downloaded.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Directory holding the cached data, with one subdirectory per year.
+    pub data_dir: PathBuf,
+    /// Never touch the network. Puzzles which are not cached fail to load.
+    pub offline: bool,
+    /// Refetch cached puzzle descriptions which are missing answers, unless
+    /// they were fetched recently.
+    pub refresh: bool,
+    /// Where to download from, without a trailing slash.
+    pub base_url: String,
+    /// Number of times to retry a request after a network or server error.
+    pub retries: u32,
+    /// Delay before the first retry. It doubles with every retry.
+    pub retry_backoff: Duration,
+    /// The session cookie. If not set, it is read from `$AOC_SESSION` or
+    /// `~/.adventofcode.session` when it is needed.
+    pub session: Option<String>,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        FetchOptions {
+            data_dir: default_data_dir(),
+            offline: false,
+            refresh: false,
+            base_url: "https://adventofcode.com".to_string(),
+            retries: 3,
+            retry_backoff: Duration::from_millis(500),
+            session: None,
         }
-        other => panic!("{:?}", other),
-    }
-}
-
-pub fn maybe_fetch_puzzle_solutions(year: u32, day: u32) -> (Option<String>, Option<String>) {
-    let cookie = get_cookie();
-    let cache_dir = dirs::cache_dir()
-        .unwrap()
-        .join("aoc-data")
-        .join(format!("{year}"));
-    fs::create_dir_all(&cache_dir).ok();
-    let puzzle_descr_file = cache_dir.join(format!("puzzle{day}.txt"));
-    let contents = match puzzle_descr_file.try_exists() {
-        Ok(true) => fs::read_to_string(puzzle_descr_file).unwrap(),
-        Ok(false) => {
-            let url = format!("https://adventofcode.com/{year}/day/{day}");
-            let cookieheader = format!("session={cookie}");
-            println!("\u{1f385} (Re)downloading puzzle description");
-            let contents = reqwest::blocking::Client::new()
-                .get(url)
-                .header(header::COOKIE, cookieheader.trim())
-                .send()
-                .unwrap()
-                .text()
-                .unwrap();
-            fs::write(puzzle_descr_file, &contents).unwrap();
-            contents
+    }
+}
+
+/// `$AOC_DATA_DIR` if set, otherwise `aoc-data` in the user's cache directory.
+pub fn default_data_dir() -> PathBuf {
+    env::var_os("AOC_DATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dirs::cache_dir().unwrap_or_default().join("aoc-data"))
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// The puzzle is not unlocked until the given time.
+    Locked(DateTime<Utc>),
+    /// There is no puzzle on this day of the year.
+    NoPuzzle(u32, u32),
+    /// Running offline, and the file is not in the cache.
+    NotCached(PathBuf),
+    /// The puzzle description does not have an example with this number.
+    NoExample(usize),
+    /// No session cookie in `$AOC_SESSION` or `~/.adventofcode.session`.
+    NoSession,
+    /// The session cookie was not accepted, and we got a login page instead.
+    LoggedOut,
+    /// The server responded with an error status.
+    Http {
+        status: u16,
+        url: String,
+    },
+    /// The request failed without a response.
+    Network(reqwest::Error),
+    Io(io::Error),
+}
+
+impl FetchError {
+    /// Whether trying again later could succeed.
+    fn is_transient(&self) -> bool {
+        match self {
+            FetchError::Network(_) => true,
+            FetchError::Http { status, .. } => *status >= 500,
+            _ => false,
         }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Locked(unlock) => write!(
+                f,
+                "locked until {}",
+                unlock
+                    .with_timezone(&utils::PUZZLE_TIMEZONE)
+                    .format("%Y-%m-%d %H:%M %Z")
+            ),
+            FetchError::NoPuzzle(year, day) => write!(f, "there is no puzzle for {year} day {day}"),
+            FetchError::NotCached(path) => {
+                write!(f, "{} is not cached, and running offline", path.display())
+            }
+            FetchError::NoExample(n) => write!(f, "the puzzle description has no example {n}"),
+            FetchError::NoSession => write!(
+                f,
+                "no session cookie found in $AOC_SESSION or ~/.adventofcode.session"
+            ),
+            FetchError::LoggedOut => write!(
+                f,
+                "the session cookie was rejected, it has probably expired"
+            ),
+            FetchError::Http { status, url } => write!(f, "HTTP status {status} for {url}"),
+            FetchError::Network(err) => write!(f, "{err}"),
+            FetchError::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<io::Error> for FetchError {
+    fn from(err: io::Error) -> Self {
+        FetchError::Io(err)
+    }
+}
+
+pub fn maybe_fetch_puzzle_data(
+    year: u32,
+    day: u32,
+    opts: &FetchOptions,
+) -> Result<String, FetchError> {
+    let puzzle_input_file = year_dir(year, opts)?.join(format!("input{day}.txt"));
+    ensure_cached_or_unlocked(&puzzle_input_file, year, day)?;
+    cached_or_fetch(&puzzle_input_file, opts, || {
+        fetch(
+            &format!("{}/{year}/day/{day}/input", opts.base_url),
+            &format!("Fetching input data for {year} day {day}"),
+            opts,
+        )
+    })
+}
 
-        other => panic!("{:?}", other),
+pub fn maybe_fetch_puzzle_solutions(
+    year: u32,
+    day: u32,
+    opts: &FetchOptions,
+) -> Result<(Option<String>, Option<String>), FetchError> {
+    let contents = maybe_fetch_puzzle_descr(year, day, opts)?;
+    let mut answers_in_text = answers(&contents).into_iter();
+    let p1 = answers_in_text.next();
+    let p2 = answers_in_text.next();
+    Ok((p1, p2))
+}
+
+/// An example from a puzzle description, with the answers the description
+/// gives for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub expected: (Option<String>, Option<String>),
+}
+
+pub fn maybe_fetch_puzzle_examples(
+    year: u32,
+    day: u32,
+    opts: &FetchOptions,
+) -> Result<Vec<Example>, FetchError> {
+    Ok(examples(&maybe_fetch_puzzle_descr(year, day, opts)?))
+}
+
+/// Extracts the candidate examples from a puzzle description. Every
+/// `<pre><code>` block is a candidate, and the last `<code><em>` in the
+/// description of a part is taken to be the answer for its examples. Part 2
+/// usually reuses the examples of part 1, so those get the part 2 answer too
+/// unless part 2 has examples of its own.
+pub fn examples(puzzle_descr: &str) -> Vec<Example> {
+    let article = lazy_regex::regex!(r#"(?s)<article class="day-desc">(.*?)</article>"#);
+    let block = lazy_regex::regex!(r"(?s)<pre><code>(.*?)</code></pre>");
+    let answer = lazy_regex::regex!(r"<code><em>([^<]*)</em></code>");
+
+    let parts = article
+        .captures_iter(puzzle_descr)
+        .map(|part| {
+            let part = part.get(1).unwrap().as_str();
+            let blocks = block
+                .captures_iter(part)
+                .map(|b| html_text(b.get(1).unwrap().as_str()))
+                .collect::<Vec<_>>();
+            let answer = answer
+                .captures_iter(part)
+                .last()
+                .map(|a| html_text(a.get(1).unwrap().as_str()));
+            (blocks, answer)
+        })
+        .collect::<Vec<_>>();
+
+    let (p1_blocks, p1_answer) = parts.first().cloned().unwrap_or_default();
+    let (p2_blocks, p2_answer) = parts.get(1).cloned().unwrap_or_default();
+    let p1_p2_answer = p2_answer.clone().filter(|_| p2_blocks.is_empty());
+
+    let p1_examples = p1_blocks.into_iter().map(|input| Example {
+        input,
+        expected: (p1_answer.clone(), p1_p2_answer.clone()),
+    });
+    let p2_examples = p2_blocks.into_iter().map(|input| Example {
+        input,
+        expected: (None, p2_answer.clone()),
+    });
+    p1_examples.chain(p2_examples).collect()
+}
+
+/// Strips the tags from a fragment of HTML, and unescapes the entities used
+/// in puzzle descriptions.
+fn html_text(html: &str) -> String {
+    let tag = lazy_regex::regex!(r"<[^>]*>");
+    puzzle_descr::unescape(&tag.replace_all(html, ""))
+}
+
+/// The puzzle description, from the cache if possible.
+pub fn maybe_fetch_puzzle_descr(
+    year: u32,
+    day: u32,
+    opts: &FetchOptions,
+) -> Result<String, FetchError> {
+    year_dir(year, opts)?;
+    let puzzle_descr_file = puzzle_descr_file(year, day, opts);
+    ensure_cached_or_unlocked(&puzzle_descr_file, year, day)?;
+    let fetch_descr = || {
+        fetch(
+            &format!("{}/{year}/day/{day}", opts.base_url),
+            "(Re)downloading puzzle description",
+            opts,
+        )
     };
+    let mut contents = cached_or_fetch(&puzzle_descr_file, opts, fetch_descr)?;
+
+    // Day 25 only has a single answer to give.
+    let expected_answers = if day == 25 { 1 } else { 2 };
+    // A description which was just fetched above is recent as well
+    if opts.refresh
+        && !opts.offline
+        && answers(&contents).len() < expected_answers
+        && utils::is_unlocked(year, day)
+        && !fetched_recently(&puzzle_descr_file)
+    {
+        match fetch_descr() {
+            Ok(refreshed) => {
+                cache(&puzzle_descr_file, &refreshed)?;
+                contents = refreshed;
+            }
+            Err(err) => eprintln!("Could not refresh {year} day {day}: {err}"),
+        }
+    }
+    Ok(contents)
+}
 
+fn answers(puzzle_descr: &str) -> Vec<String> {
     let re = lazy_regex::regex!(r"Your puzzle answer was <code>([^<]+)</code>");
-    let mut answers_in_text = re
-        .captures_iter(&contents)
-        .map(|capture| capture.get(1).unwrap().as_str());
+    re.captures_iter(puzzle_descr)
+        .map(|capture| capture.get(1).unwrap().as_str().to_string())
+        .collect()
+}
+
+/// When a cached file was fetched, if known.
+pub fn fetch_time(file: &Path) -> Option<DateTime<Utc>> {
+    let time = fs::read_to_string(fetch_time_file(file)).ok()?;
+    DateTime::parse_from_rfc3339(time.trim())
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Whether a cached file was fetched less than [`REFRESH_INTERVAL`] ago.
+fn fetched_recently(file: &Path) -> bool {
+    fetch_time(file).is_some_and(|time| {
+        (Utc::now() - time)
+            .to_std()
+            .map_or(true, |age| age < REFRESH_INTERVAL)
+    })
+}
+
+fn fetch_time_file(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_owned();
+    name.push(".fetched");
+    PathBuf::from(name)
+}
 
-    let p1 = answers_in_text.next().map(|s| s.to_string());
-    let p2 = answers_in_text.next().map(|s| s.to_string());
-    (p1, p2)
+/// Writes a fetched file to the cache, and records when it was fetched.
+fn cache(file: &Path, contents: &str) -> Result<(), FetchError> {
+    fs::write(file, contents)?;
+    fs::write(fetch_time_file(file), Utc::now().to_rfc3339())?;
+    Ok(())
 }
 
-fn get_cookie() -> String {
-    fs::read_to_string(dirs::home_dir().unwrap().join(".adventofcode.session"))
-        .expect("Could not find cookiefile")
+/// Path of the cached puzzle description.
+pub(crate) fn puzzle_descr_file(year: u32, day: u32, opts: &FetchOptions) -> PathBuf {
+    opts.data_dir
+        .join(format!("{year}"))
+        .join(format!("puzzle{day}.txt"))
+}
+
+pub(crate) fn year_dir(year: u32, opts: &FetchOptions) -> Result<PathBuf, FetchError> {
+    let dir = opts.data_dir.join(format!("{year}"));
+    if !opts.offline {
+        fs::create_dir_all(&dir)?;
+    }
+    Ok(dir)
+}
+
+/// Puzzles which are not unlocked yet are never fetched.
+fn ensure_cached_or_unlocked(file: &Path, year: u32, day: u32) -> Result<(), FetchError> {
+    if file.try_exists()? {
+        Ok(())
+    } else {
+        ensure_unlocked(year, day)
+    }
+}
+
+/// Fails for puzzles which are not unlocked yet, or which do not exist.
+pub(crate) fn ensure_unlocked(year: u32, day: u32) -> Result<(), FetchError> {
+    match utils::unlock_time(year, day) {
+        Some(unlock) if unlock > Utc::now() => Err(FetchError::Locked(unlock)),
+        Some(_) => Ok(()),
+        None => Err(FetchError::NoPuzzle(year, day)),
+    }
+}
+
+/// Returns the contents of `file` if it is cached. Otherwise fetches and
+/// caches it. Nothing is cached if the fetch fails.
+fn cached_or_fetch(
+    file: &Path,
+    opts: &FetchOptions,
+    fetch: impl FnOnce() -> Result<String, FetchError>,
+) -> Result<String, FetchError> {
+    if file.try_exists()? {
+        Ok(fs::read_to_string(file)?)
+    } else if opts.offline {
+        Err(FetchError::NotCached(file.to_path_buf()))
+    } else {
+        let contents = fetch()?;
+        cache(file, &contents)?;
+        Ok(contents)
+    }
+}
+
+pub(crate) fn client() -> Result<Client, FetchError> {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(FetchError::Network)
+}
+
+pub(crate) fn cookie_header(opts: &FetchOptions) -> Result<String, FetchError> {
+    Ok(format!("session={}", get_cookie(opts)?.trim()))
+}
+
+fn fetch(url: &str, status: &str, opts: &FetchOptions) -> Result<String, FetchError> {
+    let cookieheader = cookie_header(opts)?;
+    let client = client()?;
+
+    eprintln!("\u{1f385} {status}");
+    let mut retry = 0;
+    loop {
+        match try_fetch(&client, url, &cookieheader) {
+            Err(err) if err.is_transient() && retry < opts.retries => {
+                let delay = opts.retry_backoff * 2u32.pow(retry);
+                eprintln!("\u{1f385} {err}, retrying in {delay:?}");
+                thread::sleep(delay);
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn try_fetch(client: &Client, url: &str, cookieheader: &str) -> Result<String, FetchError> {
+    let response = client
+        .get(url)
+        .header(header::COOKIE, cookieheader)
+        .send()
+        .map_err(FetchError::Network)?;
+    let status = response.status();
+    let body = response.text().map_err(FetchError::Network)?;
+
+    // Inputs are refused with a "Please log in" message, and puzzle pages
+    // are served without the answers but with a login link.
+    if body.contains("Please log in") || body.contains("/auth/login") {
+        Err(FetchError::LoggedOut)
+    } else if !status.is_success() {
+        Err(FetchError::Http {
+            status: status.as_u16(),
+            url: url.to_string(),
+        })
+    } else {
+        Ok(body)
+    }
+}
+
+/// The session cookie, from the options, `$AOC_SESSION` or
+/// `~/.adventofcode.session`.
+fn get_cookie(opts: &FetchOptions) -> Result<String, FetchError> {
+    if let Some(session) = &opts.session {
+        return Ok(session.clone());
+    } else if let Ok(session) = env::var("AOC_SESSION") {
+        return Ok(session);
+    }
+    dirs::home_dir()
+        .and_then(|home| fs::read_to_string(home.join(".adventofcode.session")).ok())
+        .ok_or(FetchError::NoSession)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{fetch_options, serve};
+
+    #[test]
+    fn test_fetch_and_cache_input() {
+        let (url, requests) = serve(&[(200, "1 2 3\n")]);
+        let opts = fetch_options("cache", url);
+        assert_eq!("1 2 3\n", maybe_fetch_puzzle_data(2023, 1, &opts).unwrap());
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /2023/day/1/input "));
+        assert!(request.contains("cookie: session=mock-session"));
+        assert!(request.contains(&format!("user-agent: {USER_AGENT}")));
+
+        // The second time, the input comes from the cache
+        let offline = FetchOptions {
+            offline: true,
+            ..opts.clone()
+        };
+        assert_eq!(
+            "1 2 3\n",
+            maybe_fetch_puzzle_data(2023, 1, &offline).unwrap()
+        );
+        fs::remove_dir_all(&opts.data_dir).ok();
+    }
+
+    #[test]
+    fn test_refresh_missing_answers() {
+        let answer = |a| format!("<p>Your puzzle answer was <code>{a}</code>.</p>");
+        let both = answer(1) + &answer(2);
+        let (url, requests) = serve(&[(200, &both), (200, &answer(1)), (200, &both)]);
+        let opts = FetchOptions {
+            refresh: true,
+            ..fetch_options("refresh", url)
+        };
+        for year in [2023, 2999] {
+            fs::create_dir_all(opts.data_dir.join(format!("{year}"))).unwrap();
+            fs::write(puzzle_descr_file(year, 1, &opts), answer(1)).unwrap();
+        }
+
+        let expected = (Some("1".to_string()), Some("2".to_string()));
+        assert_eq!(
+            expected,
+            maybe_fetch_puzzle_solutions(2023, 1, &opts).unwrap()
+        );
+        assert!(fetch_time(&puzzle_descr_file(2023, 1, &opts)).is_some());
+        assert_eq!(1, requests.try_iter().count());
+
+        // A page which was just downloaded is not refreshed right away, even
+        // when it is still missing an answer
+        let expected = (Some("1".to_string()), None);
+        for _ in 0..2 {
+            assert_eq!(
+                expected,
+                maybe_fetch_puzzle_solutions(2023, 2, &opts).unwrap()
+            );
+        }
+        assert_eq!(1, requests.try_iter().count());
+
+        // Puzzles which are not unlocked yet are not refetched
+        let expected = (Some("1".to_string()), None);
+        assert_eq!(
+            expected,
+            maybe_fetch_puzzle_solutions(2999, 1, &opts).unwrap()
+        );
+        assert_eq!(0, requests.try_iter().count());
+        fs::remove_dir_all(&opts.data_dir).ok();
+    }
+
+    #[test]
+    fn test_examples() {
+        let descr = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Trebuchet?! ---</h2>
+<p>For example:</p>
+<pre><code>1abc2
+<em>pqr3</em>stu8vwx
+</code></pre>
+<p>Adding these together produces <code><em>50</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>54304</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>The answer is <code>x &lt; y</code>, which gives <code><em>29</em></code>.</p>
+</article>
+</main>"#;
+        assert_eq!(
+            vec![Example {
+                input: "1abc2\npqr3stu8vwx\n".to_string(),
+                expected: (Some("50".to_string()), Some("29".to_string()))
+            }],
+            examples(descr)
+        );
+    }
+
+    #[test]
+    fn test_login_page_is_not_cached() {
+        let (url, _requests) = serve(&[(
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+        )]);
+        let opts = fetch_options("login", url);
+        assert!(matches!(
+            maybe_fetch_puzzle_data(2023, 1, &opts),
+            Err(FetchError::LoggedOut)
+        ));
+        assert!(!opts.data_dir.join("2023/input1.txt").exists());
+        fs::remove_dir_all(&opts.data_dir).ok();
+    }
+
+    #[test]
+    fn test_retry_server_errors() {
+        let (url, requests) = serve(&[(500, ""), (502, ""), (200, "42\n")]);
+        let opts = fetch_options("retry", url);
+        assert_eq!("42\n", maybe_fetch_puzzle_data(2023, 2, &opts).unwrap());
+        assert_eq!(3, requests.iter().count());
+        fs::remove_dir_all(&opts.data_dir).ok();
+    }
+
+    #[test]
+    fn test_client_errors_are_not_retried() {
+        let (url, requests) = serve(&[(404, "Not Found"), (200, "42\n")]);
+        let opts = fetch_options("not-found", url);
+        assert!(matches!(
+            maybe_fetch_puzzle_data(2023, 3, &opts),
+            Err(FetchError::Http { status: 404, .. })
+        ));
+        assert_eq!(1, requests.try_iter().count());
+        assert!(!opts.data_dir.join("2023/input3.txt").exists());
+        fs::remove_dir_all(&opts.data_dir).ok();
+    }
 }
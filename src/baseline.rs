@@ -0,0 +1,161 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Day, PuzzleOutcome, PuzzleResult, PuzzleRun, SolverResult, TimingStats, Year};
+
+/// Timing statistics of a single puzzle, as stored in a baseline.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BaselineEntry {
+    pub year: Year,
+    pub day: Day,
+    pub iters: u32,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub p95_ns: u64,
+    pub stddev_ns: u64,
+}
+
+/// A named set of timings to compare later runs against.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Baseline {
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl BaselineEntry {
+    fn new(year: Year, day: Day, iters: u32, stats: &TimingStats) -> Self {
+        let ns = |d: Duration| d.as_nanos() as u64;
+        BaselineEntry {
+            year,
+            day,
+            iters,
+            min_ns: ns(stats.min),
+            median_ns: ns(stats.median),
+            mean_ns: ns(stats.mean),
+            p95_ns: ns(stats.p95),
+            stddev_ns: ns(stats.stddev),
+        }
+    }
+}
+
+impl Baseline {
+    /// Creates a baseline from the puzzles which were solved without errors.
+    pub fn from_runs(runs: &[PuzzleRun]) -> Self {
+        let entries = runs
+            .iter()
+            .filter_map(|run| {
+                timed_result(run).map(|result| {
+                    BaselineEntry::new(run.info.year, run.info.day, result.iters, &result.stats)
+                })
+            })
+            .collect();
+        Baseline { entries }
+    }
+
+    /// Path of a named baseline, stored next to the puzzle data.
+    pub fn path(data_dir: &Path, name: &str) -> PathBuf {
+        data_dir.join("baselines").join(format!("{name}.json"))
+    }
+
+    pub fn load(data_dir: &Path, name: &str) -> io::Result<Self> {
+        let contents = fs::read_to_string(Baseline::path(data_dir, name))?;
+        serde_json::from_str(&contents).map_err(io::Error::other)
+    }
+
+    pub fn save(&self, data_dir: &Path, name: &str) -> io::Result<()> {
+        let path = Baseline::path(data_dir, name);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(
+            path,
+            serde_json::to_string_pretty(self).map_err(io::Error::other)?,
+        )
+    }
+
+    pub fn get(&self, year: Year, day: Day) -> Option<&BaselineEntry> {
+        self.entries.iter().find(|e| e.year == year && e.day == day)
+    }
+
+    /// Relative change in median time of a run compared to this baseline,
+    /// in percent. Positive values mean the run got slower.
+    pub fn delta_pct(&self, run: &PuzzleRun) -> Option<f64> {
+        let result = timed_result(run)?;
+        let entry = self.get(run.info.year, run.info.day)?;
+        if entry.median_ns == 0 {
+            return None;
+        }
+        let median_ns = result.stats.median.as_nanos() as f64;
+        Some((median_ns / entry.median_ns as f64 - 1.0) * 100.0)
+    }
+
+    /// Runs which got slower than the baseline by more than `threshold_pct`.
+    pub fn regressions<'a>(
+        &self,
+        runs: &'a [PuzzleRun],
+        threshold_pct: f64,
+    ) -> Vec<(&'a PuzzleRun, f64)> {
+        runs.iter()
+            .filter_map(|run| self.delta_pct(run).map(|delta| (run, delta)))
+            .filter(|(_, delta)| *delta > threshold_pct)
+            .collect()
+    }
+}
+
+// Timings of failing solvers are not meaningful, so they are not compared.
+fn timed_result(run: &PuzzleRun) -> Option<&PuzzleResult> {
+    match &run.outcome {
+        PuzzleOutcome::Done(result) if !matches!(result.results.0, SolverResult::Error(_)) => {
+            Some(result)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PuzzleInfo;
+    use itertools::Itertools;
+
+    fn run(day: Day, median_us: u64) -> PuzzleRun {
+        let median = Duration::from_micros(median_us);
+        PuzzleRun {
+            info: PuzzleInfo {
+                year: 2023,
+                day,
+                input: String::new(),
+                expected: (None, None),
+            },
+            outcome: PuzzleOutcome::Done(Box::new(PuzzleResult {
+                time: median,
+                iters: 1,
+                results: (
+                    SolverResult::Unknown(String::new()),
+                    SolverResult::Unknown(String::new()),
+                ),
+                stats: TimingStats::from_samples(&[median], false),
+                stages: None,
+            })),
+        }
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = Baseline::from_runs(&[run(1, 100), run(2, 100)]);
+        let runs = [run(1, 105), run(2, 150), run(3, 1000)];
+        assert_eq!(Some(5.0), baseline.delta_pct(&runs[0]).map(f64::round));
+        assert_eq!(None, baseline.delta_pct(&runs[2]));
+
+        let regressions = baseline.regressions(&runs, 10.0);
+        assert_eq!(
+            vec![2],
+            regressions.iter().map(|(r, _)| r.info.day).collect_vec()
+        );
+    }
+}
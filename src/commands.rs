@@ -0,0 +1,162 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use aoc2023rust::{
+    aoc_fetcher,
+    puzzle_descr::{self, Style},
+    runner::{self, RunOptions},
+    submit::{self, Verdict},
+    Day, PuzzleOutcome, SolverResult, Year,
+};
+use indicatif::ProgressBar;
+
+use crate::{scaffold, Cli};
+
+/// Runs the solver for a puzzle and submits its answer for one part.
+pub fn submit(args: &Cli, year: Year, day: Day, part: u8) {
+    let fetch_opts = args.fetch_options();
+    let puzzle = runner::load_puzzle(year, day, &fetch_opts).unwrap_or_else(|err| {
+        eprintln!("Could not load {year} day {day}: {err}");
+        exit(2);
+    });
+    let opts = RunOptions {
+        benchmark: false,
+        ..args.run_options()
+    };
+
+    let result = match runner::run_puzzle(&puzzle, &opts, &ProgressBar::hidden()) {
+        PuzzleOutcome::Done(result) => result,
+        PuzzleOutcome::Panicked(msg) => fail(&format!("The solver panicked: {msg}")),
+        PuzzleOutcome::TimedOut(timeout) => {
+            fail(&format!("The solver timed out after {timeout:?}"))
+        }
+        PuzzleOutcome::NoInput(msg) => fail(&format!("No input: {msg}")),
+        PuzzleOutcome::Locked(_) => fail(&format!("{year} day {day} is not unlocked yet")),
+        PuzzleOutcome::Disabled => fail(&format!("The solver for {year} day {day} is disabled")),
+        PuzzleOutcome::NotRegistered => fail(&format!("No solver for {year} day {day}")),
+    };
+    let answer = match if part == 1 {
+        result.results.0
+    } else {
+        result.results.1
+    } {
+        SolverResult::Unknown(answer) => answer,
+        SolverResult::Ok(answer) => {
+            println!("Part {part} is already solved, the answer is {answer}");
+            return;
+        }
+        SolverResult::Incorrect(actual, expected) => fail(&format!(
+            "Part {part} is already solved, the answer is {expected} and not {actual}"
+        )),
+        SolverResult::Error(err) => fail(&format!("The solver failed: {err}")),
+    };
+
+    println!("Submitting {answer} for {year} day {day} part {part}");
+    match submit::submit(year, day, part, &answer, &fetch_opts) {
+        Ok(Verdict::Correct) => println!("\u{2b50} That's the right answer!"),
+        Ok(verdict) => fail(&format!("\u{274c} {verdict}")),
+        Err(err) => fail(&err.to_string()),
+    }
+}
+
+/// Prints the description of a puzzle, or writes it to
+/// `src/y<year>/day<NN>/README.md`.
+pub fn show(args: &Cli, year: Year, day: Day, markdown: bool, readme: bool) {
+    let fetch_opts = args.fetch_options();
+    let descr = aoc_fetcher::maybe_fetch_puzzle_descr(year, day, &fetch_opts)
+        .unwrap_or_else(|err| fail(&format!("Could not load {year} day {day}: {err}")));
+    let page_url = format!("{}/{year}/day/{day}", fetch_opts.base_url);
+    let style = if markdown || readme || !args.color() {
+        Style::Markdown
+    } else {
+        Style::Ansi
+    };
+    let text = puzzle_descr::render(&descr, &page_url, style);
+    if text.trim().is_empty() {
+        fail(&format!(
+            "The cached page for {year} day {day} has no description, try --refresh"
+        ));
+    }
+
+    if readme {
+        let dir = src_dir()
+            .join(format!("y{year}"))
+            .join(format!("day{day:02}"));
+        let path = dir.join("README.md");
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(&path, text))
+            .unwrap_or_else(|err| fail(&format!("Could not write {}: {err}", path.display())));
+        println!("Wrote {}", path.display());
+    } else {
+        print!("{text}");
+    }
+}
+
+/// Creates `src/y<year>/day<NN>.rs` from a template, with the first example
+/// of the puzzle as a test, and registers it. The year module is created if
+/// it does not exist yet.
+pub fn new_day(args: &Cli, year: Year, day: Day) {
+    let src = src_dir();
+    let day_file = src.join(format!("y{year}")).join(format!("day{day:02}.rs"));
+    if day_file.exists() {
+        fail(&format!("{} already exists", day_file.display()));
+    }
+
+    // The description is only used for the title and the example, so the
+    // day can be created before it is unlocked.
+    let descr = aoc_fetcher::maybe_fetch_puzzle_descr(year, day, &args.fetch_options())
+        .map_err(|err| eprintln!("Could not load the description, continuing without: {err}"))
+        .ok();
+    let title = descr
+        .as_deref()
+        .and_then(puzzle_descr::title)
+        .unwrap_or_else(|| "TODO".to_string());
+    let examples = descr
+        .as_deref()
+        .map(aoc_fetcher::examples)
+        .unwrap_or_default();
+
+    let year_file = src.join(format!("y{year}.rs"));
+    let year_module = if year_file.exists() {
+        read(&year_file)
+    } else {
+        let lib_file = src.join("lib.rs");
+        write(&lib_file, &scaffold::declare_year(&read(&lib_file), year));
+        let registry_file = src.join("registry.rs");
+        let registry = scaffold::register_year(&read(&registry_file), year)
+            .unwrap_or_else(|err| fail(&format!("{}: {err}", registry_file.display())));
+        write(&registry_file, &registry);
+        scaffold::year_module(year)
+    };
+    let year_module = scaffold::add_day(&year_module, day, &title)
+        .unwrap_or_else(|err| fail(&format!("{}: {err}", year_file.display())));
+    write(&year_file, &year_module);
+
+    fs::create_dir_all(day_file.parent().unwrap())
+        .unwrap_or_else(|err| fail(&format!("Could not create {}: {err}", src.display())));
+    write(&day_file, &scaffold::day_module(examples.first()));
+}
+
+/// The source directory of the crate, where new days are created.
+fn src_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path)
+        .unwrap_or_else(|err| fail(&format!("Could not read {}: {err}", path.display())))
+}
+
+fn write(path: &Path, contents: &str) {
+    fs::write(path, contents)
+        .unwrap_or_else(|err| fail(&format!("Could not write {}: {err}", path.display())));
+    println!("Wrote {}", path.display());
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    exit(1);
+}
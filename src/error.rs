@@ -0,0 +1,82 @@
+use std::{fmt, str::FromStr};
+
+/// An error returned from [`crate::Solver::solve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The input could not be parsed. Line and column are 1-based.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input was parsed, but the solver could not find a solution.
+    Solve(String),
+}
+
+impl SolveError {
+    /// Creates a parse error located at `token`, which must be a slice of
+    /// `input`. The line and column are computed from the position of the
+    /// token within the input.
+    pub fn parse_at(input: &str, token: &str, message: impl Into<String>) -> Self {
+        let offset = (token.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        debug_assert!(offset <= input.len(), "token is not a slice of input");
+        let before = &input[..offset.min(input.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        SolveError::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    pub fn solve(message: impl Into<String>) -> Self {
+        SolveError::Solve(message.into())
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at {line}:{column}: {message}"),
+            SolveError::Solve(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Parses `token` (a slice of `input`) into a `T`, returning a parse error
+/// pointing at the token if it is not valid.
+pub fn parse_token<T: FromStr>(input: &str, token: &str) -> Result<T, SolveError> {
+    token.parse().map_err(|_| {
+        SolveError::parse_at(
+            input,
+            token,
+            format!("expected {}, got '{token}'", std::any::type_name::<T>()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_at_position() {
+        let input = "12 34\n56 x8\n";
+        let token = &input[9..11];
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 2,
+                column: 4,
+                message: "expected i32, got 'x8'".to_string()
+            }),
+            parse_token::<i32>(input, token)
+        );
+        assert_eq!(Ok(34), parse_token::<i32>(input, &input[3..5]));
+    }
+}
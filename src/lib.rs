@@ -0,0 +1,137 @@
+#![feature(int_roundings)]
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+extern crate lazy_static;
+
+pub mod aoc_fetcher;
+pub mod baseline;
+pub mod error;
+#[cfg(test)]
+mod mock_server;
+pub mod puzzle_descr;
+pub mod registry;
+pub mod runner;
+pub mod stats;
+pub mod submit;
+pub mod utils;
+// pub mod y2015;
+// pub mod y2016;
+// pub mod y2017;
+// pub mod y2018;
+// pub mod y2019;
+// pub mod y2020;
+// pub mod y2021;
+// pub mod y2022;
+pub mod y2023;
+
+pub use error::SolveError;
+pub use stats::{StageTimes, TimingStats};
+
+// Types
+pub type Year = u32;
+pub type Day = u32;
+
+#[derive(Debug)]
+pub enum SolverResult {
+    Ok(String),
+    Incorrect(String, String), // actual, result
+    Unknown(String),           // puzzle result is still unknown
+    Error(SolveError),         // solver failed to produce a result
+}
+
+pub trait Solver<T1, T2> {
+    fn solve(&self, input: &str) -> Result<(T1, T2), SolveError>;
+
+    /// Like [`Solver::solve`], but also returns how long each stage took, for
+    /// solvers which are split into stages (see [`StagedSolver`]).
+    fn solve_staged(&self, input: &str) -> (Result<(T1, T2), SolveError>, Option<StageTimes>) {
+        (self.solve(input), None)
+    }
+}
+
+/// A solver which parses the input once, and then solves each part from the
+/// parsed input. The stages are timed separately when running the puzzle.
+/// Every `StagedSolver` is also a [`Solver`].
+pub trait StagedSolver {
+    type Parsed;
+    type P1;
+    type P2;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, SolveError>;
+    fn part1(&self, parsed: &Self::Parsed) -> Result<Self::P1, SolveError>;
+    fn part2(&self, parsed: &Self::Parsed) -> Result<Self::P2, SolveError>;
+}
+
+impl<S: StagedSolver> Solver<S::P1, S::P2> for S {
+    fn solve(&self, input: &str) -> Result<(S::P1, S::P2), SolveError> {
+        let parsed = self.parse(input)?;
+        Ok((self.part1(&parsed)?, self.part2(&parsed)?))
+    }
+
+    fn solve_staged(
+        &self,
+        input: &str,
+    ) -> (Result<(S::P1, S::P2), SolveError>, Option<StageTimes>) {
+        let t = Instant::now();
+        let parsed = match self.parse(input) {
+            Ok(parsed) => parsed,
+            Err(err) => return (Err(err), None),
+        };
+        let parse = t.elapsed();
+
+        let t = Instant::now();
+        let p1 = self.part1(&parsed);
+        let part1 = t.elapsed();
+
+        let t = Instant::now();
+        let p2 = self.part2(&parsed);
+        let part2 = t.elapsed();
+
+        let times = StageTimes {
+            parse,
+            part1,
+            part2,
+        };
+        match (p1, p2) {
+            (Ok(p1), Ok(p2)) => (Ok((p1, p2)), Some(times)),
+            (Err(err), _) | (_, Err(err)) => (Err(err), None),
+        }
+    }
+}
+
+// Structs
+
+#[derive(Debug)]
+pub struct PuzzleResult {
+    pub time: Duration, // mean time per iteration
+    pub iters: u32,
+    pub results: (SolverResult, SolverResult),
+    pub stats: TimingStats,
+    pub stages: Option<StageTimes>, // mean time per stage, for staged solvers
+}
+
+#[derive(Debug, Clone)]
+pub struct PuzzleInfo {
+    pub year: Year,
+    pub day: Day,
+    pub input: String,
+    pub expected: (Option<String>, Option<String>),
+}
+
+#[derive(Debug)]
+pub enum PuzzleOutcome {
+    Done(Box<PuzzleResult>),
+    Panicked(String),      // solver panicked, with the panic message
+    TimedOut(Duration),    // a solver call ran longer than the timeout
+    NoInput(String),       // puzzle input could not be loaded, with the reason
+    Locked(DateTime<Utc>), // puzzle is not unlocked until the given time
+    Disabled,              // solver is registered, but disabled
+    NotRegistered,         // no solver registered for this day
+}
+
+#[derive(Debug)]
+pub struct PuzzleRun {
+    pub info: PuzzleInfo,
+    pub outcome: PuzzleOutcome,
+}
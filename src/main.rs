@@ -1,68 +1,33 @@
-#![feature(int_roundings)]
-use clap::Parser;
+use aoc2023rust::{
+    aoc_fetcher::{self, FetchError, FetchOptions},
+    baseline::Baseline,
+    registry,
+    runner::{self, RunOptions},
+    utils, Day, PuzzleInfo, PuzzleOutcome, PuzzleRun, Year,
+};
+use chrono::{Datelike, Utc};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
 use indicatif::{MultiProgress, ProgressBar};
-use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use std::{
-    fmt::Display,
-    time::{Duration, Instant},
+    fs,
+    io::{stdout, IsTerminal, Read},
+    path::PathBuf,
+    thread,
+    time::Duration,
 };
-extern crate lazy_static;
 
-mod aoc_fetcher;
+mod commands;
+mod output;
+mod scaffold;
 mod table;
-mod utils;
-// mod y2015;
-// mod y2016;
-// mod y2017;
-// mod y2018;
-// mod y2019;
-// mod y2020;
-// mod y2021;
-// mod y2022;
-mod y2023;
-
-// Types
-pub type Year = u32;
-pub type Day = u32;
-
-#[derive(Debug)]
-enum SolverResult {
-    Ok(String),
-    Incorrect(String, String), // actual, result
-    Unknown(String),           // puzzle result is still unknown
-}
-
-pub trait Solver<T1, T2> {
-    fn solve(&self, input: &str) -> (T1, T2);
-}
-
-// Structs
-
-#[derive(Debug)]
-pub struct PuzzleResult {
-    time: Duration,
-    iters: u32,
-    results: (SolverResult, SolverResult),
-}
-
-#[derive(Debug)]
-struct PuzzleInfo {
-    year: Year,
-    day: Day,
-    input: String,
-    expected: (Option<String>, Option<String>),
-}
-
-#[derive(Debug)]
-struct PuzzleRun {
-    info: PuzzleInfo,
-    result: PuzzleResult,
-}
 
 #[derive(Parser, Debug)]
-struct Cli {
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(
         short = 'y',
         long,
@@ -75,10 +40,60 @@ struct Cli {
         short = 'd',
         long,
         num_args(1..),
+        value_parser = clap::value_parser!(u32).range(1..=25),
         help = "Run a single puzzle. If not specified, runs all puzzles for the specified year."
     )]
     days: Option<Vec<u32>>,
 
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["year", "days", "input"],
+        help = "Run the puzzle unlocked today"
+    )]
+    today: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["year", "days", "input", "today"],
+        help = "Wait for the next puzzle to unlock, then run it"
+    )]
+    wait: bool,
+
+    #[arg(
+        short = 'i',
+        long,
+        value_name = "PATH",
+        help = "Read the puzzle input from a file ('-' for stdin) instead of the cache. Requires a single day."
+    )]
+    input: Option<PathBuf>,
+
+    #[arg(
+        short = 'e',
+        long,
+        value_name = "N",
+        conflicts_with = "input",
+        help = "Run against example N from the puzzle description instead of the puzzle input"
+    )]
+    example: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "ANSWER",
+        requires = "input",
+        help = "Expected answer for part 1 of the given input"
+    )]
+    expect_p1: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "ANSWER",
+        requires = "input",
+        help = "Expected answer for part 2 of the given input"
+    )]
+    expect_p2: Option<String>,
+
     #[arg(
         short = 'b',
         long,
@@ -90,6 +105,7 @@ struct Cli {
     #[arg(
         long,
         default_value_t = 1000,
+        value_parser = clap::value_parser!(u32).range(1..),
         help = "Maximum number of iteration (when benchmarking)"
     )]
     max_iter: u32,
@@ -101,9 +117,111 @@ struct Cli {
     )]
     max_msecs: u32,
 
-    #[arg(long, default_value_t = false, help = "Sort by time")]
+    #[arg(
+        long,
+        default_value_t = 1,
+        help = "Number of untimed warm-up iterations (when benchmarking)"
+    )]
+    warmup: u32,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Leave outliers out of the timing statistics (when benchmarking)"
+    )]
+    drop_outliers: bool,
+
+    #[arg(
+        long,
+        help = "Report a puzzle as timed out if a single solver call runs longer than this many msecs"
+    )]
+    timeout: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Save the timings as a named baseline"
+    )]
+    save_baseline: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Compare the timings against a named baseline"
+    )]
+    baseline: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = 10.0,
+        value_name = "PERCENT",
+        help = "Exit with an error if a puzzle got this much slower than the baseline"
+    )]
+    regression_threshold: f64,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Directory for cached puzzle data and baselines [default: $AOC_DATA_DIR or ~/.cache/aoc-data]"
+    )]
+    data_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Never download puzzle data, only use what is cached"
+    )]
+    offline: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Refetch cached puzzle descriptions which are still missing answers, at most every 15 minutes"
+    )]
+    refresh: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "sort_by",
+        help = "Sort by time (same as --sort-by time)"
+    )]
     sort: bool,
 
+    #[arg(long, value_enum, value_name = "KEY", help = "Sort the results")]
+    sort_by: Option<output::SortKey>,
+
+    #[arg(long, default_value_t = false, help = "Reverse the sort order")]
+    reverse: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "COLUMNS",
+        help = "Comma-separated columns to show in the table and Markdown output [default: all]"
+    )]
+    columns: Option<Vec<output::Column>>,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "Do not color the output (also when NO_COLOR is set, or not writing to a terminal)"
+    )]
+    no_color: bool,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Mask the answers, e.g. for sharing timings publicly"
+    )]
+    redact: bool,
+
+    #[arg(long, value_enum, default_value_t = output::Format::Table, help = "Output format")]
+    format: output::Format,
+
     #[arg(
         short = 'p',
         long,
@@ -111,24 +229,133 @@ struct Cli {
         help = "Run solvers in parallel"
     )]
     parallel: bool,
+
+    #[arg(
+        short = 'l',
+        long,
+        default_value_t = false,
+        help = "List registered solvers instead of running them"
+    )]
+    list: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the solver for a puzzle, and submit its answer for one part
+    Submit {
+        #[arg(short = 'y', long)]
+        year: Year,
+
+        #[arg(short = 'd', long, value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: Day,
+
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+    },
+
+    /// Show the description of a puzzle, as Markdown or as formatted text
+    Show {
+        #[arg(short = 'y', long)]
+        year: Year,
+
+        #[arg(short = 'd', long, value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: Day,
+
+        /// Print Markdown even when writing to a terminal
+        #[arg(long)]
+        markdown: bool,
+
+        /// Write the description as Markdown to a README.md next to the
+        /// day's module, instead of printing it
+        #[arg(long)]
+        readme: bool,
+    },
+
+    /// Create the module for a new day, and register its solver
+    New {
+        #[arg(short = 'y', long)]
+        year: Year,
+
+        #[arg(short = 'd', long, value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: Day,
+    },
+}
+
+impl Cli {
+    fn fetch_options(&self) -> FetchOptions {
+        FetchOptions {
+            data_dir: self
+                .data_dir
+                .clone()
+                .unwrap_or_else(aoc_fetcher::default_data_dir),
+            offline: self.offline,
+            refresh: self.refresh,
+            ..FetchOptions::default()
+        }
+    }
+
+    fn shows(&self, column: output::Column) -> bool {
+        self.columns.as_ref().is_none_or(|cs| cs.contains(&column))
+    }
+
+    /// Colors are used on a terminal, unless turned off with `--no-color` or
+    /// `NO_COLOR`.
+    fn color(&self) -> bool {
+        !self.no_color && std::env::var_os("NO_COLOR").is_none() && stdout().is_terminal()
+    }
+
+    fn run_options(&self) -> RunOptions {
+        RunOptions {
+            benchmark: self.benchmark,
+            max_iter: self.max_iter,
+            max_msecs: self.max_msecs,
+            warmup: self.warmup,
+            drop_outliers: self.drop_outliers,
+            timeout: self.timeout.map(Duration::from_millis),
+        }
+    }
 }
 
 fn main() {
     let args = Cli::parse();
-    let days = get_puzzles(&args);
+    match args.command {
+        Some(Command::Submit { year, day, part }) => {
+            return commands::submit(&args, year, day, part)
+        }
+        Some(Command::Show {
+            year,
+            day,
+            markdown,
+            readme,
+        }) => return commands::show(&args, year, day, markdown, readme),
+        Some(Command::New { year, day }) => return commands::new_day(&args, year, day),
+        None => {}
+    }
+    if args.list {
+        println!("{}", table::make_registry_table(&args));
+        return;
+    }
+
+    let fetch_opts = args.fetch_options();
+    let baseline = args.baseline.as_ref().map(|name| {
+        Baseline::load(&fetch_opts.data_dir, name).unwrap_or_else(|err| {
+            eprintln!("Could not load baseline '{name}': {err}");
+            std::process::exit(2);
+        })
+    });
+
+    let days = get_puzzles(&args, &fetch_opts);
+    let opts = args.run_options();
     let mut results: Vec<PuzzleRun> = if args.parallel {
         let m = MultiProgress::new();
         let results = days
             .into_par_iter()
-            .filter_map(|puzzle_info| {
+            .map(|puzzle| {
                 let pb = m.add(ProgressBar::new_spinner());
                 pb.enable_steady_tick(Duration::from_millis(150));
-                let result = run_one_puzzle_with_progress(&puzzle_info, &args, &pb);
+                let run = run_loaded(puzzle, &opts, &pb);
                 pb.finish_and_clear();
-                result.map(|result| PuzzleRun {
-                    info: puzzle_info,
-                    result,
-                })
+                run
             })
             .collect::<Vec<_>>();
         m.clear().unwrap();
@@ -138,157 +365,188 @@ fn main() {
         pb.enable_steady_tick(Duration::from_millis(150));
         let results = days
             .into_iter()
-            .filter_map(|puzzle_info| {
-                run_one_puzzle_with_progress(&puzzle_info, &args, &pb).map(|result| PuzzleRun {
-                    info: puzzle_info,
-                    result,
-                })
-            })
+            .map(|puzzle| run_loaded(puzzle, &opts, &pb))
             .collect::<Vec<_>>();
         pb.finish_and_clear();
         results
     };
 
-    let table = table::make_table(&mut results, &args);
-    println!("{table}");
+    let sort_by = args.sort_by.or(args.sort.then_some(output::SortKey::Time));
+    if let Some(key) = sort_by {
+        output::sort(&mut results, key, args.reverse);
+    }
+    if args.redact {
+        output::redact(&mut results);
+    }
+
+    println!("{}", output::render(&results, &args, baseline.as_ref()));
+
+    if let Some(name) = &args.save_baseline {
+        if let Err(err) = Baseline::from_runs(&results).save(&fetch_opts.data_dir, name) {
+            eprintln!("Could not save baseline '{name}': {err}");
+            std::process::exit(2);
+        }
+    }
+
+    if let Some(baseline) = &baseline {
+        let regressions = baseline.regressions(&results, args.regression_threshold);
+        for (run, delta) in &regressions {
+            eprintln!(
+                "Year {} day {} regressed by {delta:.1}%",
+                run.info.year, run.info.day
+            );
+        }
+        if !regressions.is_empty() {
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Runs a puzzle, unless it could not be loaded.
+fn run_loaded(
+    puzzle: Result<PuzzleInfo, PuzzleRun>,
+    opts: &RunOptions,
+    pb: &ProgressBar,
+) -> PuzzleRun {
+    match puzzle {
+        Ok(puzzle_info) => PuzzleRun {
+            outcome: runner::run_puzzle(&puzzle_info, opts, pb),
+            info: puzzle_info,
+        },
+        Err(run) => run,
+    }
 }
 
-fn get_puzzles(args: &Cli) -> Vec<PuzzleInfo> {
-    if let Some(years) = &args.year {
+/// Loads the puzzles to run. Puzzles which could not be loaded are returned
+/// as a run with a `NoInput` outcome.
+fn get_puzzles(args: &Cli, fetch_opts: &FetchOptions) -> Vec<Result<PuzzleInfo, PuzzleRun>> {
+    if let Some(path) = &args.input {
+        vec![Ok(get_puzzle_from_file(path, args))]
+    } else if args.wait {
+        let (year, day) = wait_for_next_puzzle();
+        vec![load_puzzle(year, day, args, fetch_opts)]
+    } else if args.today {
+        let (year, day) = utils::todays_puzzle()
+            .unwrap_or_else(|| fail(ErrorKind::InvalidValue, "no puzzle was unlocked today"));
+        vec![load_puzzle(year, day, args, fetch_opts)]
+    } else if let Some(years) = &args.year {
         years
             .iter()
-            .flat_map(|y| get_puzzles_for_year(*y, args))
+            .flat_map(|y| get_puzzles_for_year(*y, args, fetch_opts))
             .collect()
     } else {
-        get_puzzles_for_year(utils::current_year(), args)
+        get_puzzles_for_year(utils::current_year(), args, fetch_opts)
+    }
+}
+
+fn get_puzzle_from_file(path: &PathBuf, args: &Cli) -> PuzzleInfo {
+    let year = match args.year.as_deref() {
+        None => utils::current_year(),
+        Some([year]) => *year,
+        Some(_) => fail(
+            ErrorKind::ArgumentConflict,
+            "--input requires a single year",
+        ),
+    };
+    let day = match args.days.as_deref() {
+        Some([day]) => *day,
+        _ => fail(ErrorKind::ArgumentConflict, "--input requires a single day"),
+    };
+
+    let input = if path.as_os_str() == "-" {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input).map(|_| input)
+    } else {
+        fs::read_to_string(path)
+    };
+    let input = input.unwrap_or_else(|err| {
+        fail(
+            ErrorKind::Io,
+            &format!("could not read input from {}: {err}", path.display()),
+        )
+    });
+
+    PuzzleInfo {
+        year,
+        day,
+        input,
+        expected: (args.expect_p1.clone(), args.expect_p2.clone()),
     }
 }
 
-fn get_puzzles_for_year(year: Year, args: &Cli) -> Vec<PuzzleInfo> {
+fn fail(kind: ErrorKind, message: &str) -> ! {
+    Cli::command().error(kind, message).exit()
+}
+
+fn get_puzzles_for_year(
+    year: Year,
+    args: &Cli,
+    fetch_opts: &FetchOptions,
+) -> Vec<Result<PuzzleInfo, PuzzleRun>> {
     args.days
         .clone()
-        .unwrap_or_else(|| (1..=25).collect_vec())
+        .unwrap_or_else(|| default_days(year))
         .iter()
-        .map(|&d| PuzzleInfo {
-            year,
-            day: d,
-            input: aoc_fetcher::maybe_fetch_puzzle_data(year, d),
-            expected: aoc_fetcher::maybe_fetch_puzzle_solutions(year, d),
-        })
+        .map(|&day| load_puzzle(year, day, args, fetch_opts))
         .collect()
 }
 
-fn run_one_puzzle_with_progress(
-    pi: &PuzzleInfo,
-    args: &Cli,
-    pb: &ProgressBar,
-) -> Option<PuzzleResult> {
-    match (pi.year, pi.day) {
-        (2023, 1) => Some(run_with_types(pi, args, pb, &y2023::day01::Solution)),
-        (2023, 2) => Some(run_with_types(pi, args, pb, &y2023::day02::Solution)),
-        (2023, 3) => Some(run_with_types(pi, args, pb, &y2023::day03::Solution)),
-        (2023, 4) => Some(run_with_types(pi, args, pb, &y2023::day04::Solution)),
-        (2023, 5) => Some(run_with_types(pi, args, pb, &y2023::day05::Solution)),
-        (2023, 6) => Some(run_with_types(pi, args, pb, &y2023::day06::Solution)),
-        (2023, 7) => Some(run_with_types(pi, args, pb, &y2023::day07::Solution)),
-        (2023, 8) => Some(run_with_types(pi, args, pb, &y2023::day08::Solution)),
-        (2023, 9) => Some(run_with_types(pi, args, pb, &y2023::day09::Solution)),
-        (2023, 10) => Some(run_with_types(pi, args, pb, &y2023::day10::Solution)),
-        (2023, 11) => Some(run_with_types(pi, args, pb, &y2023::day11::Solution)),
-        (2023, 12) => Some(run_with_types(pi, args, pb, &y2023::day12::Solution)),
-        (2023, 13) => Some(run_with_types(pi, args, pb, &y2023::day13::Solution)),
-        (2023, 14) => Some(run_with_types(pi, args, pb, &y2023::day14::Solution)),
-        (2023, 15) => Some(run_with_types(pi, args, pb, &y2023::day15::Solution)),
-        (2023, 16) => Some(run_with_types(pi, args, pb, &y2023::day16::Solution)),
-        (2023, 17) => Some(run_with_types(pi, args, pb, &y2023::day17::Solution)),
-        (2023, 18) => Some(run_with_types(pi, args, pb, &y2023::day18::Solution)),
-        (2023, 19) => Some(run_with_types(pi, args, pb, &y2023::day19::Solution)),
-        (2023, 20) => Some(run_with_types(pi, args, pb, &y2023::day20::Solution)),
-        (2023, 21) => Some(run_with_types(pi, args, pb, &y2023::day21::Solution)),
-        // (2023, 22) => Some(run_with_types(pi, args, pb, &y2023::day22::Solution)),
-        // (2023, 23) => Some(run_with_types(pi, args, pb, &y2023::day23::Solution)),
-        // (2023, 24) => Some(run_with_types(pi, args, pb, &y2023::day24::Solution)),
-        (2023, 25) => Some(run_with_types(pi, args, pb, &y2023::day25::Solution)),
-        _ => None,
+/// Past years only run the registered days, so that several years can be
+/// run together. The current year, or a year without any solvers, shows all
+/// its days, including the ones which are locked or not solved yet.
+fn default_days(year: Year) -> Vec<Day> {
+    let registered = registry::all()
+        .filter(|reg| reg.year == year)
+        .map(|reg| reg.day)
+        .collect_vec();
+    let (_, this_year) = Utc::now().with_timezone(&utils::PUZZLE_TIMEZONE).year_ce();
+    if registered.is_empty() || year == this_year {
+        utils::puzzle_days(year).collect()
+    } else {
+        registered
     }
 }
 
-fn run_with_types<T1, T2>(
-    puzzle_info: &PuzzleInfo,
+fn load_puzzle(
+    year: Year,
+    day: Day,
     args: &Cli,
-    pb: &ProgressBar,
-    sol2: &dyn Solver<T1, T2>,
-) -> PuzzleResult
-where
-    T1: Display + Default,
-    T2: Display + Default,
-{
-    let actual: ((T1, T2), Duration, u32) = if args.benchmark {
-        let maxduration = Duration::from_millis(args.max_msecs as u64);
-        let t0 = Instant::now();
-        (0..args.max_iter)
-            .fold_while(
-                ((T1::default(), T2::default()), Duration::ZERO, 0),
-                |acc, iter| {
-                    let elapsed = t0.elapsed();
-                    let remaining_duration = if t0.elapsed() > maxduration {
-                        Duration::ZERO
-                    } else {
-                        maxduration - elapsed
-                    };
-                    pb.set_message(format!(
-                        "Year {} day {:2} \u{1f501}{:4}/{:4} {:?}",
-                        puzzle_info.year, puzzle_info.day, iter, args.max_iter, remaining_duration
-                    ));
-
-                    // Short-circuit remaining iterations if we have exceeded the time limit.
-                    if t0.elapsed().as_millis() > args.max_msecs as u128 {
-                        Done(acc)
-                    } else {
-                        let (_, dur, iters) = acc;
-                        let input = puzzle_info.input.as_str();
-                        let t = Instant::now();
-                        let actual: (T1, T2) = sol2.solve(input);
-                        let elapsed = t.elapsed();
-                        Continue((actual, dur + elapsed, iters + 1))
-                    }
-                },
-            )
-            .into_inner()
-    } else {
-        pb.set_message(format!("Year {} day {}", puzzle_info.year, puzzle_info.day));
-        let t = Instant::now();
-        let actual = sol2.solve(puzzle_info.input.as_str());
-        (actual, t.elapsed(), 1)
-    };
-
-    let ((actual_p1, actual_p2), duration, iters) = actual;
-
-    let (exp1, exp2) = &puzzle_info.expected;
-    let results = (
-        check_result(actual_p1, exp1.clone()),
-        check_result(actual_p2, exp2.clone()),
-    );
-
-    PuzzleResult {
-        time: duration / iters,
-        iters,
-        results,
+    fetch_opts: &FetchOptions,
+) -> Result<PuzzleInfo, PuzzleRun> {
+    match args.example {
+        Some(n) => runner::load_example(year, day, n, fetch_opts),
+        None => runner::load_puzzle(year, day, fetch_opts),
     }
+    .map_err(|err| PuzzleRun {
+        info: PuzzleInfo {
+            year,
+            day,
+            input: String::new(),
+            expected: (None, None),
+        },
+        outcome: match err {
+            FetchError::Locked(unlock) => PuzzleOutcome::Locked(unlock),
+            err => PuzzleOutcome::NoInput(err.to_string()),
+        },
+    })
 }
 
-fn check_result<T>(actual: T, expected: Option<String>) -> SolverResult
-where
-    T: Display,
-{
-    let actual_str = actual.to_string();
-    if let Some(expected) = expected {
-        if actual_str == *expected {
-            SolverResult::Ok(actual_str)
-        } else {
-            SolverResult::Incorrect(actual_str, expected)
-        }
-    } else {
-        SolverResult::Unknown(actual_str)
+/// Counts down until the next puzzle unlocks, and returns it.
+fn wait_for_next_puzzle() -> (Year, Day) {
+    let (year, day) = utils::next_puzzle();
+    let unlock = utils::unlock_time(year, day).expect("the next puzzle has an unlock time");
+    let pb = ProgressBar::new_spinner();
+    pb.enable_steady_tick(Duration::from_millis(150));
+    while let Ok(remaining) = (unlock - Utc::now()).to_std() {
+        let secs = remaining.as_secs();
+        pb.set_message(format!(
+            "Year {year} day {day} unlocks in {}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        ));
+        thread::sleep(remaining.min(Duration::from_secs(1)));
     }
+    pb.finish_and_clear();
+    (year, day)
 }
@@ -0,0 +1,69 @@
+//! A minimal HTTP server standing in for adventofcode.com in tests.
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use crate::aoc_fetcher::FetchOptions;
+
+/// Serves the given responses, one per connection, and sends each request
+/// (headers and body) back over the returned channel.
+pub fn serve(responses: &[(u16, &str)]) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let responses: Vec<(u16, String)> =
+        responses.iter().map(|(s, b)| (*s, b.to_string())).collect();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(&stream);
+            let headers = reader
+                .by_ref()
+                .lines()
+                .map(|line| line.unwrap())
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<_>>();
+            let length = headers
+                .iter()
+                .find_map(|h| h.strip_prefix("content-length: "))
+                .map_or(0, |len| len.parse().unwrap());
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).unwrap();
+            tx.send(format!(
+                "{}\n\n{}",
+                headers.join("\n"),
+                String::from_utf8_lossy(&request_body)
+            ))
+            .unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    (url, rx)
+}
+
+/// Options for fetching from a mock server, with an empty data directory
+/// unique to the test.
+pub fn fetch_options(test: &str, base_url: String) -> FetchOptions {
+    let data_dir = env::temp_dir().join(format!("aoc-test-{test}-{}", std::process::id()));
+    fs::remove_dir_all(&data_dir).ok();
+    FetchOptions {
+        data_dir,
+        offline: false,
+        refresh: false,
+        base_url,
+        retries: 2,
+        retry_backoff: Duration::from_millis(1),
+        session: Some("mock-session".to_string()),
+    }
+}
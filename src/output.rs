@@ -0,0 +1,496 @@
+use aoc2023rust::{baseline::Baseline, PuzzleOutcome, PuzzleRun, SolverResult};
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{table, Cli};
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    Day,
+    Time,
+    /// Failures first, then unchecked and correct answers, then puzzles
+    /// which were not run.
+    Status,
+}
+
+/// Columns of the table and Markdown output.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Year,
+    Day,
+    Time,
+    /// Share of the total time, only in the table.
+    Share,
+    /// Min, median, p95 and standard deviation, when benchmarking.
+    Stats,
+    /// Parse / part 1 / part 2 times of staged solvers.
+    Stages,
+    Iterations,
+    Baseline,
+    Part1,
+    Part2,
+}
+
+/// Replaces the answers, for output which is shared publicly.
+const REDACTED: &str = "[redacted]";
+
+#[derive(Serialize)]
+struct PartRecord {
+    status: &'static str,
+    answer: Option<String>,
+    expected: Option<String>,
+}
+
+/// One row of machine-readable output.
+#[derive(Serialize)]
+struct Record {
+    year: u32,
+    day: u32,
+    status: &'static str,
+    message: Option<String>,
+    part1: Option<PartRecord>,
+    part2: Option<PartRecord>,
+    time_us: Option<u128>,
+    iterations: Option<u32>,
+    min_us: Option<u128>,
+    median_us: Option<u128>,
+    p95_us: Option<u128>,
+    stddev_us: Option<u128>,
+    outliers: Option<usize>,
+    parse_us: Option<u128>,
+    part1_us: Option<u128>,
+    part2_us: Option<u128>,
+    baseline_delta_pct: Option<f64>,
+}
+
+pub fn render(runs: &[PuzzleRun], args: &Cli, baseline: Option<&Baseline>) -> String {
+    match args.format {
+        Format::Table => table::make_table(runs, args, baseline).to_string(),
+        Format::Json => serde_json::to_string_pretty(&records(runs, baseline)).unwrap(),
+        Format::Csv => csv(&records(runs, baseline)),
+        Format::Markdown => markdown(runs, args, baseline),
+    }
+}
+
+/// Sorts the runs by a key. Puzzles which were not run stay last when sorting
+/// by time, also in reverse.
+pub fn sort(runs: &mut [PuzzleRun], key: SortKey, reverse: bool) {
+    let sort_key = |run: &PuzzleRun| match key {
+        SortKey::Day => (0, (run.info.year as u128) << 32 | run.info.day as u128),
+        SortKey::Time => match &run.outcome {
+            PuzzleOutcome::Done(result) => (0, result.time.as_nanos()),
+            _ => (1, 0),
+        },
+        SortKey::Status => (0, status_rank(run)),
+    };
+    runs.sort_by(|a, b| {
+        let ((a_group, a), (b_group, b)) = (sort_key(a), sort_key(b));
+        let order = if reverse { b.cmp(&a) } else { a.cmp(&b) };
+        a_group.cmp(&b_group).then(order)
+    });
+}
+
+fn status_rank(run: &PuzzleRun) -> u128 {
+    let failed =
+        |r: &SolverResult| matches!(r, SolverResult::Incorrect(..) | SolverResult::Error(_));
+    match &run.outcome {
+        PuzzleOutcome::Done(result) if failed(&result.results.0) || failed(&result.results.1) => 0,
+        PuzzleOutcome::Panicked(_) => 1,
+        PuzzleOutcome::TimedOut(_) => 2,
+        PuzzleOutcome::NoInput(_) => 3,
+        // Day 25 only has one part to solve
+        PuzzleOutcome::Done(result)
+            if matches!(result.results.0, SolverResult::Ok(_))
+                && (run.info.day == 25 || matches!(result.results.1, SolverResult::Ok(_))) =>
+        {
+            5
+        }
+        PuzzleOutcome::Done(_) => 4,
+        PuzzleOutcome::Locked(_) => 6,
+        PuzzleOutcome::Disabled => 7,
+        PuzzleOutcome::NotRegistered => 8,
+    }
+}
+
+/// Masks the answers of the runs, keeping whether they were correct.
+pub fn redact(runs: &mut [PuzzleRun]) {
+    let redact = |result: &mut SolverResult| match result {
+        SolverResult::Ok(answer) | SolverResult::Unknown(answer) => *answer = REDACTED.to_string(),
+        SolverResult::Incorrect(actual, expected) => {
+            *actual = REDACTED.to_string();
+            *expected = REDACTED.to_string();
+        }
+        SolverResult::Error(_) => {}
+    };
+    for run in runs {
+        if let PuzzleOutcome::Done(result) = &mut run.outcome {
+            redact(&mut result.results.0);
+            redact(&mut result.results.1);
+        }
+        run.info.expected = (None, None);
+    }
+}
+
+fn part_record(result: &SolverResult) -> PartRecord {
+    match result {
+        SolverResult::Ok(actual) => PartRecord {
+            status: "ok",
+            answer: Some(actual.clone()),
+            expected: Some(actual.clone()),
+        },
+        SolverResult::Incorrect(actual, expected) => PartRecord {
+            status: "incorrect",
+            answer: Some(actual.clone()),
+            expected: Some(expected.clone()),
+        },
+        SolverResult::Unknown(actual) => PartRecord {
+            status: "unknown",
+            answer: Some(actual.clone()),
+            expected: None,
+        },
+        SolverResult::Error(_) => PartRecord {
+            status: "error",
+            answer: None,
+            expected: None,
+        },
+    }
+}
+
+fn records(runs: &[PuzzleRun], baseline: Option<&Baseline>) -> Vec<Record> {
+    runs.iter()
+        .map(|run| {
+            let (year, day) = (run.info.year, run.info.day);
+            let not_run = |status, message| Record {
+                year,
+                day,
+                status,
+                message,
+                part1: None,
+                part2: None,
+                time_us: None,
+                iterations: None,
+                min_us: None,
+                median_us: None,
+                p95_us: None,
+                stddev_us: None,
+                outliers: None,
+                parse_us: None,
+                part1_us: None,
+                part2_us: None,
+                baseline_delta_pct: None,
+            };
+            match &run.outcome {
+                PuzzleOutcome::Done(result) => Record {
+                    year,
+                    day,
+                    status: "done",
+                    message: match &result.results.0 {
+                        SolverResult::Error(err) => Some(err.to_string()),
+                        _ => None,
+                    },
+                    part1: Some(part_record(&result.results.0)),
+                    // There is no second part on day 25
+                    part2: (day != 25).then(|| part_record(&result.results.1)),
+                    time_us: Some(result.time.as_micros()),
+                    iterations: Some(result.iters),
+                    min_us: Some(result.stats.min.as_micros()),
+                    median_us: Some(result.stats.median.as_micros()),
+                    p95_us: Some(result.stats.p95.as_micros()),
+                    stddev_us: Some(result.stats.stddev.as_micros()),
+                    outliers: Some(result.stats.outliers),
+                    parse_us: result.stages.map(|s| s.parse.as_micros()),
+                    part1_us: result.stages.map(|s| s.part1.as_micros()),
+                    part2_us: result.stages.map(|s| s.part2.as_micros()),
+                    baseline_delta_pct: baseline.and_then(|b| b.delta_pct(run)),
+                },
+                PuzzleOutcome::Panicked(msg) => not_run("panicked", Some(msg.clone())),
+                PuzzleOutcome::TimedOut(timeout) => {
+                    not_run("timed_out", Some(format!("timed out after {timeout:?}")))
+                }
+                PuzzleOutcome::NoInput(msg) => not_run("no_input", Some(msg.clone())),
+                PuzzleOutcome::Locked(unlock) => not_run(
+                    "locked",
+                    Some(format!("unlocks at {}", unlock.to_rfc3339())),
+                ),
+                PuzzleOutcome::Disabled => not_run("disabled", None),
+                PuzzleOutcome::NotRegistered => not_run("not_registered", None),
+            }
+        })
+        .collect()
+}
+
+fn csv_field(field: Option<String>) -> String {
+    let field = field.unwrap_or_default();
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+fn csv(records: &[Record]) -> String {
+    let header = "year,day,status,part1_status,part1_answer,part1_expected,\
+                  part2_status,part2_answer,part2_expected,time_us,iterations,\
+                  min_us,median_us,p95_us,stddev_us,outliers,parse_us,part1_us,part2_us,\
+                  baseline_delta_pct,message";
+    let part_fields = |part: &Option<PartRecord>| match part {
+        Some(part) => vec![
+            Some(part.status.to_string()),
+            part.answer.clone(),
+            part.expected.clone(),
+        ],
+        None => vec![None, None, None],
+    };
+
+    std::iter::once(header.to_string())
+        .chain(records.iter().map(|r| {
+            [Some(r.year.to_string()), Some(r.day.to_string())]
+                .into_iter()
+                .chain([Some(r.status.to_string())])
+                .chain(part_fields(&r.part1))
+                .chain(part_fields(&r.part2))
+                .chain([
+                    r.time_us.map(|t| t.to_string()),
+                    r.iterations.map(|i| i.to_string()),
+                    r.min_us.map(|t| t.to_string()),
+                    r.median_us.map(|t| t.to_string()),
+                    r.p95_us.map(|t| t.to_string()),
+                    r.stddev_us.map(|t| t.to_string()),
+                    r.outliers.map(|o| o.to_string()),
+                    r.parse_us.map(|t| t.to_string()),
+                    r.part1_us.map(|t| t.to_string()),
+                    r.part2_us.map(|t| t.to_string()),
+                    r.baseline_delta_pct.map(|d| format!("{d:.1}")),
+                    r.message.clone(),
+                ])
+                .map(csv_field)
+                .join(",")
+        }))
+        .join("\n")
+}
+
+fn markdown_cell(result: &SolverResult) -> String {
+    match result {
+        SolverResult::Ok(actual) => format!("\u{2705} {actual}"),
+        SolverResult::Incorrect(actual, expected) => {
+            format!("\u{274c} got {actual}, expected {expected}")
+        }
+        SolverResult::Unknown(actual) => format!("\u{2754} {actual}"),
+        SolverResult::Error(err) => format!("\u{274c} error: {err}"),
+    }
+}
+
+fn markdown(runs: &[PuzzleRun], args: &Cli, baseline: Option<&Baseline>) -> String {
+    let staged = runs
+        .iter()
+        .any(|run| matches!(&run.outcome, PuzzleOutcome::Done(r) if r.stages.is_some()));
+    let mut columns = vec![
+        (Column::Year, "Year", "-----:"),
+        (Column::Day, "Day", "----:"),
+        (Column::Time, "Time in \u{b5}s", "-----------:"),
+    ];
+    if args.benchmark {
+        columns.extend([
+            (Column::Stats, "Min", "----:"),
+            (Column::Stats, "Median", "-------:"),
+            (Column::Stats, "p95", "----:"),
+            (Column::Stats, "Std dev", "--------:"),
+        ]);
+    }
+    if staged {
+        columns.push((
+            Column::Stages,
+            "Parse / P1 / P2 in \u{b5}s",
+            "-------------------:",
+        ));
+    }
+    columns.push((Column::Iterations, "Iterations", "-----------:"));
+    if baseline.is_some() {
+        columns.push((Column::Baseline, "\u{394} baseline", "-----------:"));
+    }
+    columns.extend([
+        (Column::Part1, "Part 1", ":-------"),
+        (Column::Part2, "Part 2", ":-------"),
+    ]);
+
+    let rows = runs.iter().map(|run| {
+        let mut cells = vec![run.info.year.to_string(), run.info.day.to_string()];
+        let (time, iters, part1, part2) = match &run.outcome {
+            PuzzleOutcome::Done(result) => (
+                result.time.as_micros().to_string(),
+                result.iters.to_string(),
+                markdown_cell(&result.results.0),
+                if run.info.day == 25 {
+                    "--".to_string()
+                } else {
+                    markdown_cell(&result.results.1)
+                },
+            ),
+            PuzzleOutcome::Panicked(msg) => (
+                "--".to_string(),
+                "--".to_string(),
+                format!("\u{1f4a5} panicked: {msg}"),
+                "--".to_string(),
+            ),
+            PuzzleOutcome::TimedOut(timeout) => (
+                "--".to_string(),
+                "--".to_string(),
+                format!("\u{23f1}\u{fe0f} timed out after {timeout:?}"),
+                "--".to_string(),
+            ),
+            PuzzleOutcome::NoInput(msg) => (
+                "--".to_string(),
+                "--".to_string(),
+                format!("\u{1f4ed} no input: {msg}"),
+                "--".to_string(),
+            ),
+            PuzzleOutcome::Locked(unlock) => (
+                "--".to_string(),
+                "--".to_string(),
+                format!("\u{1f512} locked until {}", unlock.to_rfc3339()),
+                "--".to_string(),
+            ),
+            PuzzleOutcome::Disabled => (
+                "--".to_string(),
+                "--".to_string(),
+                "disabled".to_string(),
+                "--".to_string(),
+            ),
+            PuzzleOutcome::NotRegistered => (
+                "--".to_string(),
+                "--".to_string(),
+                "not registered".to_string(),
+                "--".to_string(),
+            ),
+        };
+        cells.push(time);
+        if args.benchmark {
+            match &run.outcome {
+                PuzzleOutcome::Done(result) => {
+                    let s = &result.stats;
+                    cells.extend(
+                        [s.min, s.median, s.p95, s.stddev].map(|d| d.as_micros().to_string()),
+                    )
+                }
+                _ => cells.extend(["--"; 4].map(String::from)),
+            }
+        }
+        if staged {
+            cells.push(match &run.outcome {
+                PuzzleOutcome::Done(result) => match &result.stages {
+                    Some(s) => format!(
+                        "{} / {} / {}",
+                        s.parse.as_micros(),
+                        s.part1.as_micros(),
+                        s.part2.as_micros()
+                    ),
+                    None => "--".to_string(),
+                },
+                _ => "--".to_string(),
+            });
+        }
+        cells.push(iters);
+        if let Some(baseline) = baseline {
+            cells.push(match baseline.delta_pct(run) {
+                Some(delta) => format!("{delta:+.1}%"),
+                None => "--".to_string(),
+            });
+        }
+        cells.push(markdown_escape(&part1));
+        cells.push(markdown_escape(&part2));
+        cells
+    });
+
+    let shown = |column: &Column| args.shows(*column);
+    let line = |cells: Vec<String>| {
+        let cells = cells
+            .into_iter()
+            .zip(&columns)
+            .filter(|(_, (column, _, _))| shown(column))
+            .map(|(cell, _)| cell);
+        format!("| {} |", cells.format(" | "))
+    };
+    let header = columns
+        .iter()
+        .map(|(_, name, _)| name.to_string())
+        .collect();
+    let align = columns
+        .iter()
+        .filter(|(column, _, _)| shown(column))
+        .map(|(_, _, align)| align)
+        .join("|");
+    [line(header), format!("|{align}|")]
+        .into_iter()
+        .chain(rows.map(line))
+        .join("\n")
+}
+
+fn markdown_escape(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aoc2023rust::{PuzzleInfo, PuzzleResult, TimingStats};
+
+    use super::*;
+
+    fn run(day: u32, time_us: Option<u64>) -> PuzzleRun {
+        let outcome = match time_us {
+            Some(us) => {
+                let time = Duration::from_micros(us);
+                PuzzleOutcome::Done(Box::new(PuzzleResult {
+                    time,
+                    iters: 1,
+                    results: (
+                        SolverResult::Ok("1".to_string()),
+                        SolverResult::Unknown("2".to_string()),
+                    ),
+                    stats: TimingStats::from_samples(&[time], false),
+                    stages: None,
+                }))
+            }
+            None => PuzzleOutcome::NotRegistered,
+        };
+        PuzzleRun {
+            info: PuzzleInfo {
+                year: 2023,
+                day,
+                input: String::new(),
+                expected: (None, None),
+            },
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut runs = [run(1, Some(20)), run(2, None), run(3, Some(10))];
+        let days = |runs: &[PuzzleRun]| runs.iter().map(|r| r.info.day).collect_vec();
+
+        sort(&mut runs, SortKey::Time, false);
+        assert_eq!(vec![3, 1, 2], days(&runs));
+        sort(&mut runs, SortKey::Time, true);
+        assert_eq!(vec![1, 3, 2], days(&runs));
+        sort(&mut runs, SortKey::Day, true);
+        assert_eq!(vec![3, 2, 1], days(&runs));
+
+        redact(&mut runs);
+        let PuzzleOutcome::Done(result) = &runs[0].outcome else {
+            panic!()
+        };
+        assert!(matches!(&result.results.0, SolverResult::Ok(a) if a == REDACTED));
+    }
+}
@@ -0,0 +1,234 @@
+//! Renders the puzzle descriptions cached by [`crate::aoc_fetcher`] as text.
+
+const BOLD: &str = "1";
+const ITALIC: &str = "3";
+const UNDERLINE: &str = "4";
+const BRIGHT_WHITE: &str = "97";
+const GREEN: &str = "32";
+const CYAN: &str = "36";
+const BLUE: &str = "34";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Markdown,
+    /// Text with ANSI escape codes, for the terminal.
+    Ansi,
+}
+
+/// Renders the `<article class="day-desc">` sections of a puzzle page. Links
+/// are made absolute using `page_url`, the address of the page.
+pub fn render(puzzle_descr: &str, page_url: &str, style: Style) -> String {
+    let article = lazy_regex::regex!(r#"(?s)<article class="day-desc">(.*?)</article>"#);
+    let mut renderer = Renderer {
+        style,
+        page_url,
+        out: String::new(),
+        styles: Vec::new(),
+        links: Vec::new(),
+        in_pre: false,
+    };
+    for part in article.captures_iter(puzzle_descr) {
+        // Answers are marked up as `<code><em>`, which cannot be expressed
+        // in Markdown. Emphasis around code can.
+        let html = part[1]
+            .replace("<code><em>", "<em><code>")
+            .replace("</em></code>", "</code></em>");
+        renderer.render(&html);
+    }
+    renderer.out.trim_end().to_string() + "\n"
+}
+
+/// The title of a puzzle, from the heading of its description.
+pub fn title(puzzle_descr: &str) -> Option<String> {
+    let heading = lazy_regex::regex!(r"<h2>--- Day \d+: (.*?) ---</h2>");
+    heading.captures(puzzle_descr).map(|h| unescape(&h[1]))
+}
+
+/// Unescapes the HTML entities used in puzzle descriptions.
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+struct Renderer<'a> {
+    style: Style,
+    page_url: &'a str,
+    out: String,
+    /// ANSI codes of the open tags, re-applied when an inner tag closes.
+    styles: Vec<&'static str>,
+    /// Targets of the open links.
+    links: Vec<String>,
+    in_pre: bool,
+}
+
+impl Renderer<'_> {
+    fn render(&mut self, html: &str) {
+        let token = lazy_regex::regex!(r#"<(/?)(\w+)([^>]*)>|([^<]+)"#);
+        for token in token.captures_iter(html) {
+            if let Some(text) = token.get(4) {
+                self.text(&unescape(text.as_str()));
+            } else if token[1].is_empty() {
+                self.open(&token[2], &token[3]);
+            } else {
+                self.close(&token[2]);
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.in_pre {
+            self.out.push_str(text);
+        } else if !(text.trim().is_empty() && text.contains('\n')) {
+            self.out.push_str(&text.replace('\n', " "));
+        }
+    }
+
+    fn open(&mut self, tag: &str, attrs: &str) {
+        let markdown = self.style == Style::Markdown;
+        match tag {
+            "h2" if markdown => self.out.push_str("## "),
+            "h2" => self.push_style(BOLD),
+            "pre" => {
+                self.in_pre = true;
+                if markdown {
+                    self.out.push_str("```\n");
+                } else {
+                    self.push_style(CYAN);
+                }
+            }
+            "code" if self.in_pre => {}
+            "code" if markdown => self.out.push('`'),
+            "code" => self.push_style(CYAN),
+            "em" if self.in_pre => {}
+            "em" if markdown => self.out.push_str("**"),
+            "em" => {
+                self.push_style(BOLD);
+                self.push_style(BRIGHT_WHITE);
+            }
+            "a" => {
+                let href = lazy_regex::regex!(r#"href="([^"]*)""#);
+                let target = href
+                    .captures(attrs)
+                    .map_or(String::new(), |h| self.absolute_url(&unescape(&h[1])));
+                self.links.push(target);
+                if markdown {
+                    self.out.push('[');
+                } else {
+                    self.push_style(UNDERLINE);
+                    self.push_style(BLUE);
+                }
+            }
+            "li" => self.out.push_str("- "),
+            "span" if !markdown && attrs.contains("title=") => self.push_style(ITALIC),
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, tag: &str) {
+        let markdown = self.style == Style::Markdown;
+        match tag {
+            "h2" if markdown => self.out.push_str("\n\n"),
+            "h2" => {
+                self.pop_style();
+                self.out.push_str("\n\n");
+            }
+            "p" => self.out.push_str("\n\n"),
+            "li" | "ul" => self.out.push('\n'),
+            "pre" => {
+                self.in_pre = false;
+                if !self.out.ends_with('\n') {
+                    self.out.push('\n');
+                }
+                if markdown {
+                    self.out.push_str("```\n\n");
+                } else {
+                    self.pop_style();
+                    self.out.push('\n');
+                }
+            }
+            "code" if self.in_pre => {}
+            "code" if markdown => self.out.push('`'),
+            "code" => self.pop_style(),
+            "em" if self.in_pre => {}
+            "em" if markdown => self.out.push_str("**"),
+            "em" => {
+                self.pop_style();
+                self.pop_style();
+            }
+            "a" => {
+                let target = self.links.pop().unwrap_or_default();
+                if markdown {
+                    self.out.push_str(&format!("]({target})"));
+                } else {
+                    self.pop_style();
+                    self.pop_style();
+                    self.push_style(GREEN);
+                    self.out.push_str(&format!(" <{target}>"));
+                    self.pop_style();
+                }
+            }
+            "span" if !markdown && self.styles.last() == Some(&ITALIC) => self.pop_style(),
+            _ => {}
+        }
+    }
+
+    fn push_style(&mut self, code: &'static str) {
+        self.styles.push(code);
+        self.out.push_str(&format!("\x1b[{code}m"));
+    }
+
+    fn pop_style(&mut self) {
+        self.styles.pop();
+        self.out.push_str("\x1b[0m");
+        for code in &self.styles {
+            self.out.push_str(&format!("\x1b[{code}m"));
+        }
+    }
+
+    fn absolute_url(&self, href: &str) -> String {
+        let origin = lazy_regex::regex!(r"^\w+://[^/]+");
+        if origin.is_match(href) {
+            href.to_string()
+        } else if href.starts_with('/') {
+            let origin = origin.find(self.page_url).map_or("", |o| o.as_str());
+            format!("{origin}{href}")
+        } else if href.starts_with('#') {
+            format!("{}{href}", self.page_url)
+        } else {
+            let dir = self.page_url.rsplit_once('/').map_or("", |(dir, _)| dir);
+            format!("{dir}/{href}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let descr = r#"<main><article class="day-desc"><h2>--- Day 1: Trebuchet?! ---</h2><p>The <a href="/2023/about">elves</a> need <em>help</em>:</p>
+<ul>
+<li>One <code>x &lt; y</code></li>
+</ul>
+<pre><code>1abc<em>2</em>
+</code></pre>
+<p>This produces <code><em>142</em></code>.</p>
+</article></main>"#;
+        assert_eq!(
+            "## --- Day 1: Trebuchet?! ---\n\n\
+             The [elves](https://adventofcode.com/2023/about) need **help**:\n\n\
+             - One `x < y`\n\n\
+             ```\n1abc2\n```\n\n\
+             This produces **`142`**.\n",
+            render(
+                descr,
+                "https://adventofcode.com/2023/day/1",
+                Style::Markdown
+            )
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+use indicatif::ProgressBar;
+
+use crate::{runner::RunOptions, Day, PuzzleInfo, PuzzleResult, Solver, Year};
+
+pub type RunFn = fn(&PuzzleInfo, &RunOptions, &ProgressBar) -> PuzzleResult;
+
+/// A solver registered for a given year and day.
+///
+/// Registrations are normally created using the [`solvers!`] macro, which
+/// takes care of wrapping the day's `Solution` in a type-erased runner.
+pub struct Registration {
+    pub year: Year,
+    pub day: Day,
+    pub title: &'static str,
+    pub enabled: bool,
+    pub answer_types: fn() -> (&'static str, &'static str),
+    pub run: RunFn,
+}
+
+pub enum Lookup {
+    Enabled(&'static Registration),
+    Disabled,
+    NotRegistered,
+}
+
+/// Registers the solvers for a year. Each line maps a day to its module
+/// (which must contain a `Solution` implementing [`Solver`]) and the puzzle
+/// title. Append `disabled` to keep a solver registered without running it.
+///
+/// ```ignore
+/// crate::solvers! {
+///     year: 2023;
+///     1 => day01, "Trebuchet?!";
+///     22 => day22, "Sand Slabs", disabled;
+/// }
+/// ```
+#[macro_export]
+macro_rules! solvers {
+    (@enabled) => { true };
+    (@enabled disabled) => { false };
+    (year: $year:expr; $($day:literal => $module:ident, $title:literal $(, $disabled:ident)?;)*) => {
+        pub const SOLVERS: &[$crate::registry::Registration] = &[
+            $($crate::registry::Registration {
+                year: $year,
+                day: $day,
+                title: $title,
+                enabled: $crate::solvers!(@enabled $($disabled)?),
+                answer_types: || $crate::registry::answer_types(&$module::Solution),
+                run: |pi, opts, pb| $crate::runner::run_with_types(pi, opts, pb, &$module::Solution),
+            },)*
+        ];
+    };
+}
+
+/// The solvers of each year module, ordered by year.
+const YEARS: &[&[Registration]] = &[crate::y2023::SOLVERS];
+
+/// All registered solvers, ordered by year and day.
+pub fn all() -> impl Iterator<Item = &'static Registration> {
+    YEARS.iter().flat_map(|solvers| solvers.iter())
+}
+
+pub fn lookup(year: Year, day: Day) -> Lookup {
+    match all().find(|reg| reg.year == year && reg.day == day) {
+        Some(reg) if reg.enabled => Lookup::Enabled(reg),
+        Some(_) => Lookup::Disabled,
+        None => Lookup::NotRegistered,
+    }
+}
+
+pub fn answer_types<T1, T2>(_solver: &dyn Solver<T1, T2>) -> (&'static str, &'static str)
+where
+    T1: Display + Default,
+    T2: Display + Default,
+{
+    (std::any::type_name::<T1>(), std::any::type_name::<T2>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_registrations_are_unique_and_sorted() {
+        let keys = all().map(|reg| (reg.year, reg.day)).collect_vec();
+        assert!(keys.iter().tuple_windows().all(|(a, b)| a < b));
+    }
+}
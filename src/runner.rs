@@ -0,0 +1,314 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use indicatif::ProgressBar;
+use itertools::FoldWhile::{Continue, Done};
+use itertools::Itertools;
+
+use crate::{
+    aoc_fetcher::{self, FetchError, FetchOptions},
+    registry,
+    stats::{StageTimes, TimingStats},
+    Day, PuzzleInfo, PuzzleOutcome, PuzzleResult, SolveError, Solver, SolverResult, Year,
+};
+
+/// Options controlling how a single puzzle is run.
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// Run the solver repeatedly and report the mean time.
+    pub benchmark: bool,
+    /// Maximum number of iterations (when benchmarking). At least one is run.
+    pub max_iter: u32,
+    /// Maximum number of msecs/puzzle to run (when benchmarking).
+    pub max_msecs: u32,
+    /// Number of untimed iterations to run before benchmarking.
+    pub warmup: u32,
+    /// Leave outliers out of the timing statistics (when benchmarking).
+    pub drop_outliers: bool,
+    /// Give up on a puzzle if a single solver call runs longer than this.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions {
+            benchmark: false,
+            max_iter: 1000,
+            max_msecs: 5000,
+            warmup: 1,
+            drop_outliers: false,
+            timeout: None,
+        }
+    }
+}
+
+/// Loads the input and expected answers for a puzzle. Data is only fetched
+/// for puzzles which have an enabled solver registered. A puzzle without
+/// known answers is still loaded, and only a missing input (or a puzzle which
+/// is not unlocked yet) is an error.
+pub fn load_puzzle(
+    year: Year,
+    day: Day,
+    fetch_opts: &FetchOptions,
+) -> Result<PuzzleInfo, FetchError> {
+    aoc_fetcher::ensure_unlocked(year, day)?;
+    match registry::lookup(year, day) {
+        registry::Lookup::Enabled(_) => Ok(PuzzleInfo {
+            year,
+            day,
+            input: aoc_fetcher::maybe_fetch_puzzle_data(year, day, fetch_opts)?,
+            expected: aoc_fetcher::maybe_fetch_puzzle_solutions(year, day, fetch_opts)
+                .unwrap_or_else(|err| {
+                    if !matches!(err, FetchError::NotCached(_) | FetchError::Locked(_)) {
+                        eprintln!("Could not load the answers for {year} day {day}: {err}");
+                    }
+                    (None, None)
+                }),
+        }),
+        _ => Ok(PuzzleInfo {
+            year,
+            day,
+            input: String::new(),
+            expected: (None, None),
+        }),
+    }
+}
+
+/// Loads example `n` (1-based) from the description of a puzzle, as the
+/// puzzle input. See [`aoc_fetcher::examples`].
+pub fn load_example(
+    year: Year,
+    day: Day,
+    n: usize,
+    fetch_opts: &FetchOptions,
+) -> Result<PuzzleInfo, FetchError> {
+    aoc_fetcher::ensure_unlocked(year, day)?;
+    match registry::lookup(year, day) {
+        registry::Lookup::Enabled(_) => {
+            let examples = aoc_fetcher::maybe_fetch_puzzle_examples(year, day, fetch_opts)?;
+            let example = n
+                .checked_sub(1)
+                .and_then(|i| examples.into_iter().nth(i))
+                .ok_or(FetchError::NoExample(n))?;
+            Ok(PuzzleInfo {
+                year,
+                day,
+                input: example.input,
+                expected: example.expected,
+            })
+        }
+        _ => Ok(PuzzleInfo {
+            year,
+            day,
+            input: String::new(),
+            expected: (None, None),
+        }),
+    }
+}
+
+/// Runs the registered solver for a puzzle, if there is one.
+pub fn run_puzzle(pi: &PuzzleInfo, opts: &RunOptions, pb: &ProgressBar) -> PuzzleOutcome {
+    match registry::lookup(pi.year, pi.day) {
+        registry::Lookup::Enabled(reg) => run_isolated(reg, pi, opts, pb),
+        registry::Lookup::Disabled => PuzzleOutcome::Disabled,
+        registry::Lookup::NotRegistered => PuzzleOutcome::NotRegistered,
+    }
+}
+
+// Solvers get the same stack size as the main thread would have had.
+const SOLVER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+enum SolverEvent {
+    CallStarted,
+    Finished(thread::Result<Box<PuzzleResult>>),
+}
+
+thread_local! {
+    // Set on solver threads, so that `run_with_types` can report when each
+    // call to the solver starts.
+    static SOLVER_EVENTS: RefCell<Option<Sender<SolverEvent>>> = const { RefCell::new(None) };
+}
+
+fn solver_call_started() {
+    SOLVER_EVENTS.with(|events| {
+        if let Some(tx) = &*events.borrow() {
+            tx.send(SolverEvent::CallStarted).ok();
+        }
+    });
+}
+
+/// Runs a solver on a separate thread, so that panics are caught and a
+/// runaway solver call can be abandoned when it exceeds the timeout. An
+/// abandoned solver keeps running in the background until the process exits.
+fn run_isolated(
+    reg: &'static registry::Registration,
+    pi: &PuzzleInfo,
+    opts: &RunOptions,
+    pb: &ProgressBar,
+) -> PuzzleOutcome {
+    let (tx, rx) = mpsc::channel();
+    let (pi, thread_opts, pb) = (pi.clone(), opts.clone(), pb.clone());
+    let spawned = thread::Builder::new()
+        .name(format!("{} day {}", pi.year, pi.day))
+        .stack_size(SOLVER_STACK_SIZE)
+        .spawn(move || {
+            SOLVER_EVENTS.with(|events| *events.borrow_mut() = Some(tx.clone()));
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                Box::new((reg.run)(&pi, &thread_opts, &pb))
+            }));
+            tx.send(SolverEvent::Finished(result)).ok();
+        });
+
+    if let Err(err) = spawned {
+        return PuzzleOutcome::Panicked(format!("could not start solver thread: {err}"));
+    }
+
+    // The timeout restarts whenever a new solver call starts, so it limits
+    // single calls and not the total time spent benchmarking.
+    loop {
+        let event = match opts.timeout {
+            Some(timeout) => rx.recv_timeout(timeout),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match event {
+            Ok(SolverEvent::CallStarted) => continue,
+            Ok(SolverEvent::Finished(Ok(result))) => return PuzzleOutcome::Done(result),
+            Ok(SolverEvent::Finished(Err(payload))) => {
+                return PuzzleOutcome::Panicked(panic_message(payload.as_ref()))
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                return PuzzleOutcome::TimedOut(opts.timeout.unwrap_or_default())
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return PuzzleOutcome::Panicked("solver thread exited unexpectedly".to_string())
+            }
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+pub fn run_with_types<T1, T2>(
+    puzzle_info: &PuzzleInfo,
+    opts: &RunOptions,
+    pb: &ProgressBar,
+    sol2: &dyn Solver<T1, T2>,
+) -> PuzzleResult
+where
+    T1: Display + Default,
+    T2: Display + Default,
+{
+    let input = puzzle_info.input.as_str();
+    type Samples = (Vec<Duration>, Vec<Option<StageTimes>>);
+    let actual: (Result<(T1, T2), SolveError>, Samples) = if opts.benchmark {
+        // Warm-up iterations are not timed, and do not count towards the
+        // iteration or time limits.
+        for iter in 0..opts.warmup {
+            pb.set_message(format!(
+                "Year {} day {:2} \u{1f525}{:4}/{:4}",
+                puzzle_info.year, puzzle_info.day, iter, opts.warmup
+            ));
+            solver_call_started();
+            if sol2.solve(input).is_err() {
+                break;
+            }
+        }
+
+        let maxduration = Duration::from_millis(opts.max_msecs as u64);
+        let t0 = Instant::now();
+        // At least one sample is needed for the answers and timing stats
+        (0..opts.max_iter.max(1))
+            .fold_while(
+                (Ok((T1::default(), T2::default())), (Vec::new(), Vec::new())),
+                |acc, iter| {
+                    let elapsed = t0.elapsed();
+                    let remaining_duration = if t0.elapsed() > maxduration {
+                        Duration::ZERO
+                    } else {
+                        maxduration - elapsed
+                    };
+                    pb.set_message(format!(
+                        "Year {} day {:2} \u{1f501}{:4}/{:4} {:?}",
+                        puzzle_info.year, puzzle_info.day, iter, opts.max_iter, remaining_duration
+                    ));
+
+                    // Short-circuit remaining iterations if we have exceeded the time limit.
+                    if iter > 0 && t0.elapsed().as_millis() > opts.max_msecs as u128 {
+                        Done(acc)
+                    } else {
+                        let (_, (mut samples, mut stages)) = acc;
+                        solver_call_started();
+                        let t = Instant::now();
+                        let (actual, stage_times) = sol2.solve_staged(input);
+                        samples.push(t.elapsed());
+                        stages.push(stage_times);
+                        let samples = (samples, stages);
+                        if actual.is_err() {
+                            // No point in benchmarking a failing solver
+                            Done((actual, samples))
+                        } else {
+                            Continue((actual, samples))
+                        }
+                    }
+                },
+            )
+            .into_inner()
+    } else {
+        pb.set_message(format!("Year {} day {}", puzzle_info.year, puzzle_info.day));
+        solver_call_started();
+        let t = Instant::now();
+        let (actual, stage_times) = sol2.solve_staged(input);
+        (actual, (vec![t.elapsed()], vec![stage_times]))
+    };
+
+    let (actual, (samples, stage_samples)) = actual;
+    let stats = TimingStats::from_samples(&samples, opts.drop_outliers);
+
+    let (exp1, exp2) = &puzzle_info.expected;
+    let results = match actual {
+        Ok((actual_p1, actual_p2)) => (
+            check_result(actual_p1, exp1.clone()),
+            check_result(actual_p2, exp2.clone()),
+        ),
+        Err(err) => (SolverResult::Error(err.clone()), SolverResult::Error(err)),
+    };
+
+    PuzzleResult {
+        time: stats.mean,
+        iters: samples.len() as u32,
+        results,
+        stats,
+        stages: StageTimes::mean(&stage_samples),
+    }
+}
+
+pub fn check_result<T>(actual: T, expected: Option<String>) -> SolverResult
+where
+    T: Display,
+{
+    let actual_str = actual.to_string();
+    if let Some(expected) = expected {
+        if actual_str == *expected {
+            SolverResult::Ok(actual_str)
+        } else {
+            SolverResult::Incorrect(actual_str, expected)
+        }
+    } else {
+        SolverResult::Unknown(actual_str)
+    }
+}
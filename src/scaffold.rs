@@ -0,0 +1,198 @@
+//! Generates the source of new solvers, and wires them into the crate.
+
+use aoc2023rust::{aoc_fetcher::Example, Day, Year};
+use itertools::Itertools;
+
+/// The module for a new day, with a test for the example if there is one. Its
+/// solver returns an error until it is implemented.
+pub fn day_module(example: Option<&Example>) -> String {
+    let mut module = "\
+use crate::{SolveError, Solver};
+
+pub struct Solution;
+impl Solver<i64, i64> for Solution {
+    fn solve(&self, _input: &str) -> Result<(i64, i64), SolveError> {
+        Err(SolveError::solve(\"not implemented\"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+"
+    .to_string();
+
+    let (input, expected) = example.map_or((String::new(), (None, None)), |example| {
+        (example.input.clone(), example.expected.clone())
+    });
+    let hashes = "#".repeat(
+        (0..)
+            .find(|&n| !input.contains(&format!("\"{}", "#".repeat(n))))
+            .unwrap(),
+    );
+    module += &format!("    const EXAMPLE: &str = r{hashes}\"{input}\"{hashes};\n\n");
+    module += "    #[test]\n    #[ignore = \"not solved yet\"]\n    fn test_example() {\n";
+    let answers = if expected == (None, None) {
+        "_answers"
+    } else {
+        "answers"
+    };
+    module += &format!("        let {answers} = Solution.solve(EXAMPLE).unwrap();\n");
+    for (field, answer) in [("0", expected.0), ("1", expected.1)] {
+        match answer {
+            Some(answer) => {
+                module += &format!("        assert_eq!({answer:?}, answers.{field}.to_string());\n")
+            }
+            None => {
+                module += &format!("        // assert_eq!(\"\", answers.{field}.to_string());\n")
+            }
+        }
+    }
+    module + "    }\n}\n"
+}
+
+/// A year module without any days.
+pub fn year_module(year: Year) -> String {
+    format!("crate::solvers! {{\n    year: {year};\n}}\n")
+}
+
+/// Adds a day to a year module: its `mod` declaration, and its line in the
+/// `solvers!` block. Both are kept sorted by day.
+pub fn add_day(year_module: &str, day: Day, title: &str) -> Result<String, String> {
+    let module = format!("day{day:02}");
+    let mod_line = format!("pub mod {module};");
+    let title = title.replace('\\', "\\\\").replace('"', "\\\"");
+    let solver_line = format!("    {day} => {module}, \"{title}\";");
+    let solver_day = lazy_regex::regex!(r"^\s+(\d+) =>");
+
+    let mut lines = year_module.lines().map(str::to_string).collect_vec();
+    if lines.contains(&mod_line) {
+        return Err(format!("{module} is already declared"));
+    }
+
+    let mods = lines
+        .iter()
+        .positions(|l| l.starts_with("pub mod "))
+        .collect_vec();
+    match mods.iter().find(|&&i| lines[i] > mod_line).or(mods.last()) {
+        Some(&i) if lines[i] > mod_line => lines.insert(i, mod_line),
+        Some(&i) => lines.insert(i + 1, mod_line),
+        None => lines.splice(0..0, [mod_line, String::new()]).for_each(drop),
+    }
+
+    let day_of = |line: &str| {
+        solver_day
+            .captures(line)
+            .and_then(|c| c[1].parse::<Day>().ok())
+    };
+    if lines.iter().any(|l| day_of(l) == Some(day)) {
+        return Err(format!("day {day} is already registered"));
+    }
+    let solvers = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with("year:"))
+        .ok_or("the solvers! block was not found")?;
+    let after = lines
+        .iter()
+        .enumerate()
+        .skip(solvers)
+        .take_while(|(_, l)| l.trim() != "}")
+        .filter(|(_, l)| day_of(l).is_some_and(|d| d < day))
+        .map(|(i, _)| i)
+        .last()
+        .unwrap_or(solvers);
+    lines.insert(after + 1, solver_line);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Declares a year module in `lib.rs`, uncommenting its line if it is there.
+pub fn declare_year(lib_rs: &str, year: Year) -> String {
+    let mod_line = format!("pub mod y{year};");
+    let year_mod = lazy_regex::regex!(r"^(// )?pub mod y\d{4};$");
+    let mut lines = lib_rs.lines().map(str::to_string).collect_vec();
+    if let Some(line) = lines.iter_mut().find(|l| l.ends_with(&mod_line)) {
+        *line = mod_line;
+    } else {
+        let years = lines
+            .iter()
+            .positions(|l| year_mod.is_match(l))
+            .collect_vec();
+        let position = years
+            .iter()
+            .find(|&&i| lines[i].trim_start_matches("// ") > mod_line.as_str())
+            .copied()
+            .or(years.last().map(|i| i + 1))
+            .unwrap_or(lines.len());
+        lines.insert(position, mod_line);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Adds the solvers of a year module to the list in `registry.rs`.
+pub fn register_year(registry_rs: &str, year: Year) -> Result<String, String> {
+    let list = lazy_regex::regex!(r"(?s)(const YEARS: &\[&\[Registration\]\] = &\[)(.*?)(\];)");
+    let captures = list
+        .captures(registry_rs)
+        .ok_or("the list of years was not found")?;
+    let mut years = captures[2]
+        .split(',')
+        .map(str::trim)
+        .filter(|y| !y.is_empty())
+        .map(str::to_string)
+        .collect_vec();
+    let solvers = format!("crate::y{year}::SOLVERS");
+    if !years.contains(&solvers) {
+        years.push(solvers);
+        years.sort();
+    }
+    let single_line = format!("{}{}{}", &captures[1], years.join(", "), &captures[3]);
+    let years = if single_line.len() <= 100 {
+        single_line
+    } else {
+        let items = years.iter().map(|y| format!("    {y},\n")).join("");
+        format!("{}\n{items}{}", &captures[1], &captures[3])
+    };
+    Ok(list.replace(registry_rs, years.as_str()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_day() {
+        let module = "pub mod day01;\npub mod day03;\n\ncrate::solvers! {\n    year: 2023;\n    \
+                      1 => day01, \"One\";\n    3 => day03, \"Three\";\n}\n";
+        assert_eq!(
+            "pub mod day01;\npub mod day02;\npub mod day03;\n\ncrate::solvers! {\n    year: 2023;\n    \
+             1 => day01, \"One\";\n    2 => day02, \"Two \\\"2\\\"\";\n    3 => day03, \"Three\";\n}\n",
+            add_day(module, 2, "Two \"2\"").unwrap()
+        );
+        assert!(add_day(module, 3, "Three").is_err());
+
+        assert_eq!(
+            "pub mod day01;\n\ncrate::solvers! {\n    year: 2015;\n    1 => day01, \"One\";\n}\n",
+            add_day(&year_module(2015), 1, "One").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wire_year() {
+        let lib = "pub mod utils;\n// pub mod y2015;\n// pub mod y2022;\npub mod y2023;\n";
+        assert_eq!(
+            "pub mod utils;\npub mod y2015;\n// pub mod y2022;\npub mod y2023;\n",
+            declare_year(lib, 2015)
+        );
+        assert_eq!(
+            "pub mod utils;\n// pub mod y2015;\n// pub mod y2022;\npub mod y2023;\npub mod y2024;\n",
+            declare_year(lib, 2024)
+        );
+
+        let registry = "const YEARS: &[&[Registration]] = &[crate::y2023::SOLVERS];\n";
+        assert_eq!(
+            "const YEARS: &[&[Registration]] = &[crate::y2015::SOLVERS, crate::y2023::SOLVERS];\n",
+            register_year(registry, 2015).unwrap()
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+/// Summary statistics over the durations of the benchmarked iterations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimingStats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+    pub stddev: Duration,
+    /// Number of samples the statistics are computed from.
+    pub samples: usize,
+    /// Number of samples dropped as outliers.
+    pub outliers: usize,
+}
+
+impl TimingStats {
+    /// Computes statistics over `samples`. If `drop_outliers` is set,
+    /// samples outside of Tukey's fences (1.5 times the interquartile range
+    /// below the first or above the third quartile) are left out.
+    pub fn from_samples(samples: &[Duration], drop_outliers: bool) -> TimingStats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        if drop_outliers && sorted.len() >= 4 {
+            let q1 = percentile(&sorted, 25.0);
+            let q3 = percentile(&sorted, 75.0);
+            let fence = (q3 - q1).mul_f64(1.5);
+            let (lower, upper) = (q1.saturating_sub(fence), q3 + fence);
+            sorted.retain(|d| lower <= *d && *d <= upper);
+        }
+
+        if sorted.is_empty() {
+            return TimingStats::default();
+        }
+
+        let n = sorted.len();
+        let mean = sorted.iter().sum::<Duration>() / n as u32;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        TimingStats {
+            min: sorted[0],
+            median: percentile(&sorted, 50.0),
+            mean,
+            p95: percentile(&sorted, 95.0),
+            max: sorted[n - 1],
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+            samples: n,
+            outliers: samples.len() - n,
+        }
+    }
+}
+
+/// Time spent in each stage of a [`crate::StagedSolver`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StageTimes {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+impl StageTimes {
+    /// Mean time per stage, or `None` if any of the samples is missing.
+    pub fn mean(samples: &[Option<StageTimes>]) -> Option<StageTimes> {
+        let samples: Vec<StageTimes> = samples.iter().copied().collect::<Option<_>>()?;
+        let n = u32::try_from(samples.len()).ok().filter(|n| *n > 0)?;
+        Some(StageTimes {
+            parse: samples.iter().map(|s| s.parse).sum::<Duration>() / n,
+            part1: samples.iter().map(|s| s.part1).sum::<Duration>() / n,
+            part2: samples.iter().map(|s| s.part2).sum::<Duration>() / n,
+        })
+    }
+}
+
+/// Nearest-rank percentile of a sorted, non-empty slice.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(v: &[u64]) -> Vec<Duration> {
+        v.iter().map(|us| Duration::from_micros(*us)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = TimingStats::from_samples(&micros(&[5, 1, 4, 2, 3]), false);
+        assert_eq!(Duration::from_micros(1), stats.min);
+        assert_eq!(Duration::from_micros(3), stats.median);
+        assert_eq!(Duration::from_micros(3), stats.mean);
+        assert_eq!(Duration::from_micros(5), stats.p95);
+        assert_eq!(Duration::from_micros(5), stats.max);
+        assert_eq!(Duration::from_secs_f64(2f64.sqrt() / 1e6), stats.stddev);
+        assert_eq!((5, 0), (stats.samples, stats.outliers));
+    }
+
+    #[test]
+    fn test_drop_outliers() {
+        // A slow cold run should not affect the statistics
+        let samples = micros(&[1000, 10, 11, 10, 12, 11, 10]);
+        let stats = TimingStats::from_samples(&samples, true);
+        assert_eq!((6, 1), (stats.samples, stats.outliers));
+        assert_eq!(Duration::from_micros(12), stats.max);
+
+        let stats = TimingStats::from_samples(&samples, false);
+        assert_eq!(Duration::from_micros(1000), stats.max);
+    }
+}
@@ -0,0 +1,331 @@
+use std::{fmt, fs, io, path::PathBuf, time::Duration};
+
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    aoc_fetcher::{self, FetchError, FetchOptions},
+    Day, Year,
+};
+
+/// The response to a submitted answer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint in which direction.
+    Wrong,
+    /// An answer was submitted too recently, try again after this long.
+    Wait(Duration),
+    /// The part is already solved, or not unlocked yet.
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Parses the page returned after posting an answer.
+    pub fn parse(page: &str) -> Option<Verdict> {
+        let wait = lazy_regex::regex!(r"You have (?:(\d+)m )?(\d+)s left to wait");
+        if page.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if page.contains("That's not the right answer") {
+            Some(if page.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if page.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            })
+        } else if let Some(captures) = wait.captures(page) {
+            let number = |i| captures.get(i).map_or(0, |m| m.as_str().parse().unwrap());
+            Some(Verdict::Wait(Duration::from_secs(
+                60 * number(1) + number(2),
+            )))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Some(Verdict::WrongLevel)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait(wait) => write!(f, "submitted too recently, wait {wait:?}"),
+            Verdict::WrongLevel => write!(f, "already solved, or not unlocked yet"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Guess {
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// When the guess was submitted, in RFC 3339 format.
+    pub time: String,
+}
+
+/// The answers submitted for a day, stored next to the puzzle data.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GuessLog {
+    pub guesses: Vec<Guess>,
+}
+
+/// Why an answer was not submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// The part was already solved, with this answer.
+    AlreadySolved(String),
+    /// The answer was already submitted, and was wrong.
+    AlreadyGuessed(Verdict),
+    /// The answer is at or above an answer which was too high.
+    AboveBound(String),
+    /// The answer is at or below an answer which was too low.
+    BelowBound(String),
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::AlreadySolved(answer) => write!(f, "already solved, the answer is {answer}"),
+            Rejection::AlreadyGuessed(verdict) => write!(f, "already submitted, it was {verdict}"),
+            Rejection::AboveBound(bound) => write!(f, "{bound} was already too high"),
+            Rejection::BelowBound(bound) => write!(f, "{bound} was already too low"),
+        }
+    }
+}
+
+impl GuessLog {
+    pub fn path(year: Year, day: Day, opts: &FetchOptions) -> PathBuf {
+        opts.data_dir
+            .join(format!("{year}"))
+            .join(format!("guesses{day}.json"))
+    }
+
+    /// Loads the log for a day. A missing log is empty.
+    pub fn load(year: Year, day: Day, opts: &FetchOptions) -> io::Result<Self> {
+        match fs::read_to_string(GuessLog::path(year, day, opts)) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(GuessLog::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, year: Year, day: Day, opts: &FetchOptions) -> io::Result<()> {
+        let path = GuessLog::path(year, day, opts);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(
+            path,
+            serde_json::to_string_pretty(self).map_err(io::Error::other)?,
+        )
+    }
+
+    /// Checks an answer against the earlier guesses for the same part.
+    pub fn check(&self, part: u8, answer: &str) -> Result<(), Rejection> {
+        let guesses = self.guesses.iter().filter(|g| g.part == part);
+        let number = |s: &str| s.trim().parse::<i128>().ok();
+        for guess in guesses {
+            if guess.verdict == Verdict::Correct {
+                return Err(Rejection::AlreadySolved(guess.answer.clone()));
+            } else if guess.answer == answer {
+                return Err(Rejection::AlreadyGuessed(guess.verdict.clone()));
+            }
+            if let (Some(bound), Some(answer)) = (number(&guess.answer), number(answer)) {
+                match guess.verdict {
+                    Verdict::TooHigh if answer >= bound => {
+                        return Err(Rejection::AboveBound(guess.answer.clone()))
+                    }
+                    Verdict::TooLow if answer <= bound => {
+                        return Err(Rejection::BelowBound(guess.answer.clone()))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, part: u8, answer: &str, verdict: Verdict) {
+        self.guesses.push(Guess {
+            part,
+            answer: answer.to_string(),
+            verdict,
+            time: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The answer was not submitted, because of an earlier guess.
+    Rejected(Rejection),
+    /// The response did not look like any known verdict.
+    UnknownResponse,
+    Fetch(FetchError),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Rejected(rejection) => write!(f, "not submitted: {rejection}"),
+            SubmitError::UnknownResponse => write!(f, "could not understand the response"),
+            SubmitError::Fetch(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+impl From<FetchError> for SubmitError {
+    fn from(err: FetchError) -> Self {
+        SubmitError::Fetch(err)
+    }
+}
+
+impl From<io::Error> for SubmitError {
+    fn from(err: io::Error) -> Self {
+        SubmitError::Fetch(FetchError::Io(err))
+    }
+}
+
+/// Submits an answer, unless the guess log shows that it cannot be right.
+/// Wrong and correct answers are added to the log. After a correct answer,
+/// the cached puzzle description is removed so that the answer is picked up
+/// the next time the puzzle is loaded.
+pub fn submit(
+    year: Year,
+    day: Day,
+    part: u8,
+    answer: &str,
+    opts: &FetchOptions,
+) -> Result<Verdict, SubmitError> {
+    let mut log = GuessLog::load(year, day, opts)?;
+    log.check(part, answer).map_err(SubmitError::Rejected)?;
+
+    // Answers are not retried, as a failed request may still have counted.
+    let response = aoc_fetcher::client()?
+        .post(format!("{}/{year}/day/{day}/answer", opts.base_url))
+        .header(header::COOKIE, aoc_fetcher::cookie_header(opts)?)
+        .form(&[("level", part.to_string()), ("answer", answer.to_string())])
+        .send()
+        .and_then(|response| response.text())
+        .map_err(FetchError::Network)?;
+    if response.contains("Please log in") || response.contains("/auth/login") {
+        return Err(FetchError::LoggedOut.into());
+    }
+    let verdict = Verdict::parse(&response).ok_or(SubmitError::UnknownResponse)?;
+
+    if !matches!(verdict, Verdict::Wait(_) | Verdict::WrongLevel) {
+        log.record(part, answer, verdict.clone());
+        log.save(year, day, opts)?;
+    }
+    if verdict == Verdict::Correct {
+        fs::remove_file(aoc_fetcher::puzzle_descr_file(year, day, opts)).ok();
+    }
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{fetch_options, serve};
+
+    #[test]
+    fn test_parse_verdict() {
+        let page = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+        assert_eq!(
+            Some(Verdict::Correct),
+            Verdict::parse(&page(
+                "That's the right answer! You are one gold star closer."
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::TooLow),
+            Verdict::parse(&page(
+                "That's not the right answer; your answer is too low."
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::Wait(Duration::from_secs(277))),
+            Verdict::parse(&page(
+                "You gave an answer too recently. You have 4m 37s left to wait."
+            ))
+        );
+        assert_eq!(
+            Some(Verdict::Wait(Duration::from_secs(37))),
+            Verdict::parse(&page("You have 37s left to wait."))
+        );
+        assert_eq!(None, Verdict::parse(&page("Something else")));
+    }
+
+    #[test]
+    fn test_check_bounds() {
+        let mut log = GuessLog::default();
+        log.record(1, "100", Verdict::TooHigh);
+        log.record(1, "10", Verdict::TooLow);
+        log.record(1, "abc", Verdict::Wrong);
+        log.record(2, "5", Verdict::Correct);
+
+        assert_eq!(Ok(()), log.check(1, "50"));
+        assert_eq!(
+            Err(Rejection::AboveBound("100".to_string())),
+            log.check(1, "150")
+        );
+        assert_eq!(
+            Err(Rejection::BelowBound("10".to_string())),
+            log.check(1, "5")
+        );
+        assert_eq!(
+            Err(Rejection::AlreadyGuessed(Verdict::Wrong)),
+            log.check(1, "abc")
+        );
+        assert_eq!(
+            Err(Rejection::AlreadySolved("5".to_string())),
+            log.check(2, "6")
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, requests) = serve(&[
+            (
+                200,
+                "<p>That's not the right answer; your answer is too high.</p>",
+            ),
+            (200, "<p>That's the right answer!</p>"),
+        ]);
+        let opts = fetch_options("submit", url);
+
+        assert_eq!(Verdict::TooHigh, submit(2023, 1, 1, "100", &opts).unwrap());
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2023/day/1/answer "));
+        assert!(request.ends_with("level=1&answer=100"));
+
+        // Not sent to the server
+        assert!(matches!(
+            submit(2023, 1, 1, "200", &opts),
+            Err(SubmitError::Rejected(Rejection::AboveBound(_)))
+        ));
+
+        assert_eq!(Verdict::Correct, submit(2023, 1, 1, "42", &opts).unwrap());
+        assert!(requests.recv().unwrap().ends_with("level=1&answer=42"));
+
+        let log = GuessLog::load(2023, 1, &opts).unwrap();
+        assert_eq!(
+            vec![("100", Verdict::TooHigh), ("42", Verdict::Correct)],
+            log.guesses
+                .iter()
+                .map(|g| (g.answer.as_str(), g.verdict.clone()))
+                .collect::<Vec<_>>()
+        );
+        fs::remove_dir_all(&opts.data_dir).ok();
+    }
+}
@@ -1,8 +1,14 @@
 use std::time::Duration;
 
 use comfy_table::{presets::*, Attribute, Cell, CellAlignment, Color, Table};
+use itertools::Itertools;
 
-use crate::{Cli, PuzzleRun, SolverResult};
+use aoc2023rust::{
+    baseline::Baseline, registry, utils, PuzzleOutcome, PuzzleRun, SolverResult, StageTimes,
+    TimingStats,
+};
+
+use crate::{output::Column, Cli};
 
 fn hdr_cell(text: &str) -> Cell {
     Cell::new(text)
@@ -10,52 +16,303 @@ fn hdr_cell(text: &str) -> Cell {
         .add_attribute(Attribute::Bold)
 }
 
-pub fn make_table(runs: &mut Vec<PuzzleRun>, args: &Cli) -> comfy_table::Table {
-    let mut table = Table::new();
-    let total_time = runs
+// Header of the timing statistics columns, shown when benchmarking.
+const STATS_HEADER: [&str; 4] = ["Min", "Median", "p95", "Std dev"];
+
+fn stats_cells(stats: &TimingStats) -> Vec<Cell> {
+    [stats.min, stats.median, stats.p95, stats.stddev]
         .iter()
-        .map(|run| run.result.time)
-        .max()
-        .unwrap_or(Duration::ZERO);
+        .map(|d| Cell::new(d.as_micros()).set_alignment(CellAlignment::Right))
+        .collect()
+}
 
-    table.load_preset(UTF8_FULL_CONDENSED).set_header(vec![
+fn stages_cell(stages: &StageTimes) -> Cell {
+    Cell::new(format!(
+        "{} / {} / {}",
+        stages.parse.as_micros(),
+        stages.part1.as_micros(),
+        stages.part2.as_micros()
+    ))
+    .set_alignment(CellAlignment::Right)
+}
+
+fn delta_cell(delta: Option<f64>, threshold: f64) -> Cell {
+    match delta {
+        Some(delta) => {
+            let cell = Cell::new(format!("{delta:+.1}%")).set_alignment(CellAlignment::Right);
+            if delta > threshold {
+                cell.fg(Color::Red).add_attribute(Attribute::Bold)
+            } else if delta < -threshold {
+                cell.fg(Color::Green)
+            } else {
+                cell
+            }
+        }
+        None => Cell::new("--").fg(Color::DarkGrey),
+    }
+}
+
+pub fn make_table(
+    runs: &[PuzzleRun],
+    args: &Cli,
+    baseline: Option<&Baseline>,
+) -> comfy_table::Table {
+    let mut table = Table::new();
+    let total_time: Duration = runs.iter().filter_map(solve_time).sum();
+
+    let mut header = vec![
         hdr_cell("Year"),
         hdr_cell("Day"),
         hdr_cell("Time in \u{b5}s"),
+        hdr_cell("% of total"),
         hdr_cell("Iterations"),
         hdr_cell("Part 1"),
         hdr_cell("Part 2"),
-    ]);
-
-    if args.sort {
-        runs.sort_by(|a, b| a.result.time.cmp(&b.result.time))
+    ];
+    // Only staged solvers report a breakdown, so the column is left out if
+    // none of them were run.
+    let staged = runs
+        .iter()
+        .any(|run| matches!(&run.outcome, PuzzleOutcome::Done(r) if r.stages.is_some()));
+    // The kind of each column, to leave out the ones which are not selected.
+    let mut kinds = vec![
+        Column::Year,
+        Column::Day,
+        Column::Time,
+        Column::Share,
+        Column::Iterations,
+        Column::Part1,
+        Column::Part2,
+    ];
+    if baseline.is_some() {
+        header.insert(5, hdr_cell("\u{394} baseline"));
+        kinds.insert(5, Column::Baseline);
+    }
+    if staged {
+        header.insert(4, hdr_cell("Parse / P1 / P2 in \u{b5}s"));
+        kinds.insert(4, Column::Stages);
+    }
+    if args.benchmark {
+        header.splice(4..4, STATS_HEADER.map(hdr_cell));
+        kinds.splice(4..4, STATS_HEADER.map(|_| Column::Stats));
+    }
+    let select = |row: Vec<Cell>| {
+        row.into_iter()
+            .zip(&kinds)
+            .filter(|(_, kind)| args.shows(**kind))
+            .map(|(cell, _)| cell)
+            .collect_vec()
+    };
+    let columns = header.len();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_header(select(header));
+    if !args.color() {
+        table.force_no_tty();
     }
 
     for run in runs {
-        table.add_row(vec![
-            Cell::new(run.info.year),
-            Cell::new(run.info.day),
-            Cell::new(format!("{:?}", run.result.time.as_micros()))
+        let mut row = match &run.outcome {
+            PuzzleOutcome::Done(result) => vec![
+                Cell::new(run.info.year),
+                Cell::new(run.info.day),
+                Cell::new(format!("{:?}", result.time.as_micros()))
+                    .set_alignment(CellAlignment::Right)
+                    .add_attribute(Attribute::Bold),
+                share_cell(result.time, total_time),
+                Cell::new(result.iters),
+                solution_cell(&result.results.0),
+                if run.info.day == 25 {
+                    Cell::new("--").fg(Color::DarkGrey)
+                } else {
+                    solution_cell(&result.results.1)
+                },
+            ],
+            PuzzleOutcome::Panicked(msg) => failed_row(run, format!("panicked: {msg}")),
+            PuzzleOutcome::TimedOut(timeout) => {
+                failed_row(run, format!("timed out after {timeout:?}"))
+            }
+            PuzzleOutcome::NoInput(msg) => failed_row(run, format!("no input: {msg}")),
+            PuzzleOutcome::Locked(unlock) => not_run_row(
+                run,
+                &format!(
+                    "locked until {}",
+                    unlock
+                        .with_timezone(&utils::PUZZLE_TIMEZONE)
+                        .format("%Y-%m-%d %H:%M %Z")
+                ),
+            ),
+            PuzzleOutcome::Disabled => not_run_row(run, "disabled"),
+            PuzzleOutcome::NotRegistered => not_run_row(run, "not registered"),
+        };
+        if let Some(baseline) = baseline {
+            row.insert(
+                5,
+                delta_cell(baseline.delta_pct(run), args.regression_threshold),
+            );
+        }
+        if staged {
+            row.insert(
+                4,
+                match &run.outcome {
+                    PuzzleOutcome::Done(result) => match &result.stages {
+                        Some(stages) => stages_cell(stages),
+                        None => Cell::new("--").fg(Color::DarkGrey),
+                    },
+                    _ => Cell::new("--").fg(Color::DarkGrey),
+                },
+            );
+        }
+        if args.benchmark {
+            let stats = match &run.outcome {
+                PuzzleOutcome::Done(result) => stats_cells(&result.stats),
+                _ => STATS_HEADER
+                    .map(|_| Cell::new("--").fg(Color::DarkGrey))
+                    .to_vec(),
+            };
+            row.splice(4..4, stats);
+        }
+        table.add_row(select(row));
+    }
+    // The summary comes after all rows, as the runs may be sorted by time.
+    let summary_row = |label: String, day: String, time: Duration| {
+        // Keep the label visible when the year column is left out
+        let (label, day) = if args.shows(Column::Year) {
+            (label, day)
+        } else {
+            (
+                String::new(),
+                format!("{label} {day}").trim_end().to_string(),
+            )
+        };
+        let mut row = vec![
+            Cell::new(label).fg(Color::DarkBlue),
+            Cell::new(day).fg(Color::DarkBlue),
+            Cell::new(time.as_micros())
                 .set_alignment(CellAlignment::Right)
+                .fg(Color::DarkBlue)
                 .add_attribute(Attribute::Bold),
-            Cell::new(run.result.iters),
-            solution_cell(&run.result.results.0),
-            if run.info.day == 25 {
-                Cell::new("--").fg(Color::DarkGrey)
-            } else {
-                solution_cell(&run.result.results.1)
-            },
-        ]);
+            share_cell(time, total_time),
+        ];
+        row.resize(columns, Cell::new(""));
+        row
+    };
+    let years = runs
+        .iter()
+        .map(|run| run.info.year)
+        .sorted()
+        .dedup()
+        .collect_vec();
+    if years.len() > 1 {
+        for year in years {
+            let time = runs
+                .iter()
+                .filter(|run| run.info.year == year)
+                .filter_map(solve_time)
+                .sum();
+            table.add_row(select(summary_row(
+                format!("{year} total"),
+                String::new(),
+                time,
+            )));
+        }
     }
-    table.add_row(vec![
-        Cell::new("Total").fg(Color::DarkBlue),
-        Cell::new(format!("{:?} ms", total_time.as_micros()))
-            .fg(Color::DarkBlue)
+    let slowest = runs
+        .iter()
+        .filter_map(|run| solve_time(run).map(|time| (run, time)))
+        .max_by_key(|(_, time)| *time);
+    if let Some((run, time)) = slowest {
+        let day = format!("{}/{}", run.info.year, run.info.day);
+        table.add_row(select(summary_row("Slowest".to_string(), day, time)));
+    }
+    table.add_row(select(summary_row(
+        "Total".to_string(),
+        String::new(),
+        total_time,
+    )));
+    table
+}
+
+/// The share of a solve time in the total, with a bar of eighth blocks.
+fn share_cell(time: Duration, total: Duration) -> Cell {
+    const WIDTH: usize = 10;
+    const PARTIAL: [&str; 8] = [
+        "", "\u{258f}", "\u{258e}", "\u{258d}", "\u{258c}", "\u{258b}", "\u{258a}", "\u{2589}",
+    ];
+    if total.is_zero() {
+        return Cell::new("--").fg(Color::DarkGrey);
+    }
+    let share = time.as_secs_f64() / total.as_secs_f64();
+    let eighths = (share * (WIDTH * 8) as f64).round() as usize;
+    let bar = "\u{2588}".repeat(eighths / 8) + PARTIAL[eighths % 8];
+    Cell::new(format!("{:5.1}% {bar:<WIDTH$}", share * 100.0)).fg(Color::Magenta)
+}
+
+fn solve_time(run: &PuzzleRun) -> Option<Duration> {
+    match &run.outcome {
+        PuzzleOutcome::Done(result) => Some(result.time),
+        _ => None,
+    }
+}
+
+fn failed_row(run: &PuzzleRun, status: String) -> Vec<Cell> {
+    vec![
+        Cell::new(run.info.year),
+        Cell::new(run.info.day),
+        Cell::new("--").fg(Color::DarkGrey),
+        Cell::new("--").fg(Color::DarkGrey),
+        Cell::new("--").fg(Color::DarkGrey),
+        Cell::new(status)
+            .fg(Color::Red)
             .add_attribute(Attribute::Bold),
-        Cell::new(""),
-        Cell::new(""),
-        Cell::new(""),
+        Cell::new("--").fg(Color::DarkGrey),
+    ]
+}
+
+fn not_run_row(run: &PuzzleRun, status: &str) -> Vec<Cell> {
+    vec![
+        Cell::new(run.info.year),
+        Cell::new(run.info.day),
+        Cell::new("--").fg(Color::DarkGrey),
+        Cell::new("--").fg(Color::DarkGrey),
+        Cell::new("--").fg(Color::DarkGrey),
+        Cell::new(status).fg(Color::DarkGrey),
+        Cell::new("--").fg(Color::DarkGrey),
+    ]
+}
+
+pub fn make_registry_table(args: &Cli) -> comfy_table::Table {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED).set_header(vec![
+        hdr_cell("Year"),
+        hdr_cell("Day"),
+        hdr_cell("Title"),
+        hdr_cell("Part 1 type"),
+        hdr_cell("Part 2 type"),
+        hdr_cell("Status"),
     ]);
+    if !args.color() {
+        table.force_no_tty();
+    }
+
+    registry::all()
+        .filter(|reg| args.year.as_ref().is_none_or(|ys| ys.contains(&reg.year)))
+        .filter(|reg| args.days.as_ref().is_none_or(|ds| ds.contains(&reg.day)))
+        .for_each(|reg| {
+            let (t1, t2) = (reg.answer_types)();
+            table.add_row(vec![
+                Cell::new(reg.year),
+                Cell::new(reg.day),
+                Cell::new(reg.title),
+                Cell::new(t1),
+                Cell::new(t2),
+                if reg.enabled {
+                    Cell::new("enabled").fg(Color::Green)
+                } else {
+                    Cell::new("disabled").fg(Color::DarkGrey)
+                },
+            ]);
+        });
     table
 }
 
@@ -68,5 +325,8 @@ fn solution_cell(result: &SolverResult) -> Cell {
                 .add_attribute(Attribute::Bold)
         }
         SolverResult::Unknown(actual) => Cell::new(actual).fg(Color::DarkYellow),
+        SolverResult::Error(err) => Cell::new(format!("error: {err}"))
+            .fg(Color::Red)
+            .add_attribute(Attribute::Bold),
     }
 }
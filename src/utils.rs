@@ -1,12 +1,91 @@
-use chrono::{Datelike, Local};
+use std::ops::RangeInclusive;
 
-use crate::Year;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use chrono_tz::{America::New_York, Tz};
+
+use crate::{registry, Day, Year};
 
 pub mod astar;
+pub mod geom;
+pub mod grid;
+pub mod search;
+
+/// Puzzles unlock at midnight in US Eastern time.
+pub const PUZZLE_TIMEZONE: Tz = New_York;
 
+/// The year to run by default. During December (in US Eastern time) this is
+/// the running event, otherwise the latest year with registered solvers.
 pub fn current_year() -> Year {
-    let local = Local::now();
-    let date = local.date_naive();
-    let (_, year) = date.year_ce();
-    year
+    let today = Utc::now().with_timezone(&PUZZLE_TIMEZONE).date_naive();
+    let year = today.year() as Year;
+    if today.month() == 12 {
+        year
+    } else {
+        registry::all()
+            .map(|reg| reg.year)
+            .max()
+            .unwrap_or(year - 1)
+    }
+}
+
+/// The days with a puzzle. Since 2025, there are twelve puzzles per year.
+pub fn puzzle_days(year: Year) -> RangeInclusive<Day> {
+    if year >= 2025 {
+        1..=12
+    } else {
+        1..=25
+    }
+}
+
+/// When a puzzle is unlocked: midnight US Eastern time on the day in December.
+/// `None` if the year has no puzzle on that day.
+pub fn unlock_time(year: Year, day: Day) -> Option<DateTime<Utc>> {
+    if !puzzle_days(year).contains(&day) {
+        return None;
+    }
+    PUZZLE_TIMEZONE
+        .with_ymd_and_hms(year as i32, 12, day, 0, 0, 0)
+        .single()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Days without a puzzle are never unlocked.
+pub fn is_unlocked(year: Year, day: Day) -> bool {
+    unlock_time(year, day).is_some_and(|unlock| unlock <= Utc::now())
+}
+
+/// The puzzle unlocked today (in US Eastern time), if any.
+pub fn todays_puzzle() -> Option<(Year, Day)> {
+    let today = Utc::now().with_timezone(&PUZZLE_TIMEZONE).date_naive();
+    let year = today.year() as Year;
+    (today.month() == 12 && puzzle_days(year).contains(&today.day())).then_some((year, today.day()))
+}
+
+/// The next puzzle to unlock.
+pub fn next_puzzle() -> (Year, Day) {
+    let today = Utc::now().with_timezone(&PUZZLE_TIMEZONE).date_naive();
+    let year = today.year() as Year;
+    match todays_puzzle() {
+        Some((year, day)) if day < *puzzle_days(year).end() => (year, day + 1),
+        _ if today.month() == 12 => (year + 1, 1),
+        _ => (year, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_time() {
+        assert_eq!(
+            "2023-12-01T05:00:00+00:00",
+            unlock_time(2023, 1).unwrap().to_rfc3339()
+        );
+        assert!(is_unlocked(2023, 25));
+        assert_eq!(None, unlock_time(2023, 32));
+        assert_eq!(None, unlock_time(2025, 13));
+        assert!(!is_unlocked(2023, 40));
+        assert_eq!(12, *puzzle_days(2025).end());
+    }
 }
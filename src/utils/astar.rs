@@ -1,8 +1,11 @@
 /// Borrowed from https://github.com/dszoboszlay/adventofcode/blob/main/rust/src/utils/astar.rs
-use std::collections::btree_map::Entry;
-use std::collections::BTreeMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap};
 use std::hash::Hash;
 
+use hashbrown::hash_map::Entry;
+use hashbrown::HashMap;
+
 /// An interface for representing search states.
 ///
 /// A search state needs to provide some basic information on whether it is a
@@ -10,7 +13,7 @@ use std::hash::Hash;
 /// states reachable from it.
 ///
 /// Search states also have a key used for detecting loops.
-pub trait SearchState {
+pub trait SearchState: Sized {
     /// The type of the state's key.
     type Key: Hash + Ord + Clone;
     type Iter: Iterator<Item = Self>;
@@ -22,65 +25,333 @@ pub trait SearchState {
     fn next_states(self) -> Self::Iter;
 }
 
-type StatesKey<K> = (usize, K);
+/// The states waiting to be expanded, ordered by their estimated total cost
+/// (cost plus heuristic).
+///
+/// When a state is found again with a lower total, it is pushed again. A
+/// frontier may drop the old entry right away, or keep it: entries which are
+/// popped with a higher total than the best known one are skipped.
+pub trait Frontier<S: SearchState>: Default {
+    /// Adds a state. `replaces` is the total of an earlier entry for the
+    /// same key, if there is one.
+    fn push(&mut self, total: usize, state: S, replaces: Option<usize>);
+    /// Removes a state with the lowest total.
+    fn pop(&mut self) -> Option<(usize, S)>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
 
-struct Search<S, K> {
-    states: BTreeMap<StatesKey<K>, S>,
-    best_costs: BTreeMap<K, usize>,
+/// An ordered map, which replaces entries right away. Ties are broken by key.
+pub struct BTreeFrontier<S: SearchState> {
+    states: BTreeMap<(usize, S::Key), S>,
 }
 
-impl<S: SearchState> Search<S, S::Key> {
-    fn new() -> Search<S, S::Key> {
-        Search {
+impl<S: SearchState> Default for BTreeFrontier<S> {
+    fn default() -> Self {
+        BTreeFrontier {
             states: BTreeMap::new(),
-            best_costs: BTreeMap::new(),
         }
     }
+}
+
+impl<S: SearchState> Frontier<S> for BTreeFrontier<S> {
+    fn push(&mut self, total: usize, state: S, replaces: Option<usize>) {
+        if let Some(old_total) = replaces {
+            self.states.remove(&(old_total, state.key()));
+        }
+        self.states.insert((total, state.key()), state);
+    }
+
+    fn pop(&mut self) -> Option<(usize, S)> {
+        self.states
+            .pop_first()
+            .map(|((total, _), state)| (total, state))
+    }
+
+    fn len(&self) -> usize {
+        self.states.len()
+    }
+}
+
+/// A binary heap. Replaced entries are kept until they are popped.
+pub struct HeapFrontier<S> {
+    heap: BinaryHeap<HeapEntry<S>>,
+}
+
+struct HeapEntry<S> {
+    total: usize,
+    state: S,
+}
+
+// Reversed, as the heap pops the largest entry first.
+impl<S> Ord for HeapEntry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.total.cmp(&self.total)
+    }
+}
+
+impl<S> PartialOrd for HeapEntry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> PartialEq for HeapEntry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.total == other.total
+    }
+}
+
+impl<S> Eq for HeapEntry<S> {}
+
+impl<S> Default for HeapFrontier<S> {
+    fn default() -> Self {
+        HeapFrontier {
+            heap: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<S: SearchState> Frontier<S> for HeapFrontier<S> {
+    fn push(&mut self, total: usize, state: S, _replaces: Option<usize>) {
+        self.heap.push(HeapEntry { total, state });
+    }
 
-    fn insert(&mut self, search_state: S) {
+    fn pop(&mut self) -> Option<(usize, S)> {
+        self.heap.pop().map(|entry| (entry.total, entry.state))
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+}
+
+/// A bucket per total, for small integer costs. Replaced entries are kept
+/// until they are popped.
+pub struct BucketFrontier<S> {
+    buckets: Vec<Vec<S>>,
+    // No bucket below this one holds any states.
+    lowest: usize,
+    len: usize,
+}
+
+impl<S> Default for BucketFrontier<S> {
+    fn default() -> Self {
+        BucketFrontier {
+            buckets: Vec::new(),
+            lowest: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<S: SearchState> Frontier<S> for BucketFrontier<S> {
+    fn push(&mut self, total: usize, state: S, _replaces: Option<usize>) {
+        if total >= self.buckets.len() {
+            self.buckets.resize_with(total + 1, Vec::new);
+        }
+        self.buckets[total].push(state);
+        self.lowest = self.lowest.min(total);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(usize, S)> {
+        while self.lowest < self.buckets.len() {
+            if let Some(state) = self.buckets[self.lowest].pop() {
+                self.len -= 1;
+                return Some((self.lowest, state));
+            }
+            self.lowest += 1;
+        }
+        None
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// Counters collected during a search, for comparing heuristics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Number of states whose next states were generated.
+    pub expanded: usize,
+    /// The largest number of states waiting to be expanded at once.
+    pub max_frontier: usize,
+    /// Number of times a state was found again with a lower cost.
+    pub reinserted: usize,
+}
+
+/// The outcome of [`search`].
+pub struct SearchResult<S: SearchState> {
+    /// The goal state which was reached, if any.
+    pub goal: Option<S>,
+    /// Keys of the states from the start state to the goal, both included.
+    /// Empty if no goal was reached.
+    pub path: Vec<S::Key>,
+    pub stats: SearchStats,
+}
+
+struct Search<S: SearchState, F> {
+    states: F,
+    best_costs: HashMap<S::Key, usize>,
+    // The state each state was best reached from, None for the start state.
+    parents: HashMap<S::Key, Option<S::Key>>,
+    stats: SearchStats,
+}
+
+impl<S: SearchState, F: Frontier<S>> Search<S, F> {
+    fn new() -> Search<S, F> {
+        Search {
+            states: F::default(),
+            best_costs: HashMap::new(),
+            parents: HashMap::new(),
+            stats: SearchStats::default(),
+        }
+    }
+
+    fn insert(&mut self, search_state: S, parent: Option<&S::Key>) {
         let total = search_state.cost() + search_state.heuristic();
         match self.best_costs.entry(search_state.key()) {
             Entry::Vacant(entry) => {
                 entry.insert(total);
-                self.states
-                    .insert((total, search_state.key()), search_state);
+                self.parents.insert(search_state.key(), parent.cloned());
+                self.states.push(total, search_state, None);
             }
             Entry::Occupied(mut entry) => {
                 let old_total = *entry.get();
                 if total < old_total {
-                    let mut states_key = (old_total, search_state.key());
                     entry.insert(total);
-                    self.states.remove(&states_key);
-                    states_key.0 = total;
-                    self.states.insert(states_key, search_state);
+                    self.parents.insert(search_state.key(), parent.cloned());
+                    self.states.push(total, search_state, Some(old_total));
+                    self.stats.reinserted += 1;
                 }
             }
         }
+        self.stats.max_frontier = self.stats.max_frontier.max(self.states.len());
+    }
+
+    /// Follows the parent links from a state back to the start state.
+    fn path(&self, key: S::Key) -> Vec<S::Key> {
+        let mut path = vec![key];
+        while let Some(Some(parent)) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        path
     }
 
     fn pop(&mut self) -> Option<S> {
-        match self.states.keys().next() {
-            None => None,
-            Some(key) => {
-                let key_copy = (*key).clone();
-                self.states.remove(&key_copy)
+        // Skip entries which were replaced by a cheaper one
+        while let Some((total, state)) = self.states.pop() {
+            if self.best_costs.get(&state.key()) == Some(&total) {
+                return Some(state);
             }
         }
+        None
     }
 }
 
 pub fn solve<S: SearchState>(start_state: S) -> Option<S> {
-    let mut search = Search::new();
-    search.insert(start_state);
+    search(start_state).goal
+}
+
+/// Like [`solve`], but also returns the path to the goal and statistics.
+pub fn search<S: SearchState>(start_state: S) -> SearchResult<S> {
+    search_with::<S, BTreeFrontier<S>>(start_state)
+}
+
+/// Like [`search`], with a different [`Frontier`]. [`BTreeFrontier`] works
+/// for any costs, the others can be faster for small integer costs.
+pub fn search_with<S: SearchState, F: Frontier<S>>(start_state: S) -> SearchResult<S> {
+    let mut search = Search::<S, F>::new();
+    search.insert(start_state, None);
     while let Some(state) = search.pop() {
         if state.is_goal() {
-            return Some(state);
+            let path = search.path(state.key());
+            return SearchResult {
+                goal: Some(state),
+                path,
+                stats: search.stats,
+            };
         }
 
+        let key = state.key();
+        search.stats.expanded += 1;
         state.next_states().for_each(|s| {
-            search.insert(s);
+            search.insert(s, Some(&key));
+        });
+    }
+
+    SearchResult {
+        goal: None,
+        path: Vec::new(),
+        stats: search.stats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Walks along a line of costs from position 0 to the last position.
+    struct Walk<'a> {
+        costs: &'a [usize],
+        pos: usize,
+        cost: usize,
+    }
+
+    impl SearchState for Walk<'_> {
+        type Key = usize;
+        type Iter = std::vec::IntoIter<Self>;
+
+        fn key(&self) -> usize {
+            self.pos
+        }
+        fn is_goal(&self) -> bool {
+            self.pos == self.costs.len() - 1
+        }
+        fn cost(&self) -> usize {
+            self.cost
+        }
+        fn heuristic(&self) -> usize {
+            0
+        }
+        fn next_states(self) -> Self::Iter {
+            // Steps of one or two positions
+            [self.pos + 1, self.pos + 2]
+                .into_iter()
+                .filter(|&pos| pos < self.costs.len())
+                .map(|pos| Walk {
+                    costs: self.costs,
+                    pos,
+                    cost: self.cost + self.costs[pos],
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+    }
+
+    const COSTS: [usize; 6] = [0, 1, 9, 1, 9, 1];
+
+    fn check_search<F: Frontier<Walk<'static>>>() {
+        let result = search_with::<_, F>(Walk {
+            costs: &COSTS,
+            pos: 0,
+            cost: 0,
         });
+        assert_eq!(Some(3), result.goal.map(|s| s.cost));
+        assert_eq!(vec![0, 1, 3, 5], result.path);
+        assert!(result.stats.expanded >= 3);
+        assert!(result.stats.max_frontier >= 2);
     }
 
-    None
+    #[test]
+    fn test_search_path() {
+        check_search::<BTreeFrontier<Walk>>();
+        check_search::<HeapFrontier<Walk>>();
+        check_search::<BucketFrontier<Walk>>();
+    }
 }
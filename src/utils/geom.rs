@@ -0,0 +1,336 @@
+//! Directions and points. Screen coordinates are used: `x` grows to the
+//! right and `y` grows down, so [`Dir4::Up`] is a negative `y`.
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use super::grid::Pos;
+
+/// One of the four directions along the axes, in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    /// Parses `U`, `R`, `D` and `L`, or the arrows `^`, `>`, `v` and `<`.
+    pub fn from_char(c: char) -> Option<Dir4> {
+        match c {
+            'U' | '^' => Some(Dir4::Up),
+            'R' | '>' => Some(Dir4::Right),
+            'D' | 'v' => Some(Dir4::Down),
+            'L' | '<' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    pub fn turn_right(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Dir4 {
+        Dir4::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The vector of a single step in this direction.
+    pub fn offset(self) -> Point2 {
+        match self {
+            Dir4::Up => Point2::new(0, -1),
+            Dir4::Right => Point2::new(1, 0),
+            Dir4::Down => Point2::new(0, 1),
+            Dir4::Left => Point2::new(-1, 0),
+        }
+    }
+
+    /// The next position in a [`super::grid::Grid`], which is row first.
+    pub fn step(self, (row, col): Pos) -> Pos {
+        let Point2 { x, y } = self.offset();
+        (row + y as i32, col + x as i32)
+    }
+}
+
+/// One of the eight directions including the diagonals, in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    /// Turns by 45 degrees.
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns by 45 degrees.
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Dir8 {
+        Dir8::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn offset(self) -> Point2 {
+        let (x, y) = match self {
+            Dir8::Up => (0, -1),
+            Dir8::UpRight => (1, -1),
+            Dir8::Right => (1, 0),
+            Dir8::DownRight => (1, 1),
+            Dir8::Down => (0, 1),
+            Dir8::DownLeft => (-1, 1),
+            Dir8::Left => (-1, 0),
+            Dir8::UpLeft => (-1, -1),
+        };
+        Point2::new(x, y)
+    }
+
+    pub fn step(self, (row, col): Pos) -> Pos {
+        let Point2 { x, y } = self.offset();
+        (row + y as i32, col + x as i32)
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Dir8 {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+/// A set of [`Dir4`]s in a single byte, e.g. to record the directions in
+/// which a cell was entered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirSet(u8);
+
+impl DirSet {
+    pub const EMPTY: DirSet = DirSet(0);
+
+    pub fn contains(self, dir: Dir4) -> bool {
+        self.0 & (1 << dir as u8) != 0
+    }
+
+    /// Adds a direction, returning whether it was not in the set yet.
+    pub fn insert(&mut self, dir: Dir4) -> bool {
+        let added = !self.contains(dir);
+        self.0 |= 1 << dir as u8;
+        added
+    }
+
+    pub fn remove(&mut self, dir: Dir4) {
+        self.0 &= !(1 << dir as u8);
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Dir4> {
+        Dir4::ALL.into_iter().filter(move |&dir| self.contains(dir))
+    }
+}
+
+impl FromIterator<Dir4> for DirSet {
+    fn from_iter<I: IntoIterator<Item = Dir4>>(dirs: I) -> DirSet {
+        let mut set = DirSet::EMPTY;
+        for dir in dirs {
+            set.insert(dir);
+        }
+        set
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point2 {
+    pub const fn new(x: i64, y: i64) -> Point2 {
+        Point2 { x, y }
+    }
+}
+
+impl Point3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Point3 {
+        Point3 { x, y, z }
+    }
+}
+
+impl From<(i64, i64)> for Point2 {
+    fn from((x, y): (i64, i64)) -> Point2 {
+        Point2 { x, y }
+    }
+}
+
+impl From<(i64, i64, i64)> for Point3 {
+    fn from((x, y, z): (i64, i64, i64)) -> Point3 {
+        Point3 { x, y, z }
+    }
+}
+
+impl Add<Dir4> for Point2 {
+    type Output = Point2;
+
+    fn add(self, dir: Dir4) -> Point2 {
+        self + dir.offset()
+    }
+}
+
+impl Add<Dir8> for Point2 {
+    type Output = Point2;
+
+    fn add(self, dir: Dir8) -> Point2 {
+        self + dir.offset()
+    }
+}
+
+// Component-wise arithmetic, the same for both point types.
+macro_rules! point_ops {
+    ($point:ident { $($field:ident),+ }) => {
+        impl $point {
+            pub const ORIGIN: $point = $point { $($field: 0),+ };
+
+            /// The sum of the absolute coordinate differences.
+            pub fn manhattan(self, other: $point) -> i64 {
+                0 $(+ self.$field.abs_diff(other.$field) as i64)+
+            }
+        }
+
+        impl Add for $point {
+            type Output = $point;
+
+            fn add(self, other: $point) -> $point {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = $point;
+
+            fn sub(self, other: $point) -> $point {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul<i64> for $point {
+            type Output = $point;
+
+            fn mul(self, factor: i64) -> $point {
+                $point { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = $point;
+
+            fn neg(self) -> $point {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T> AddAssign<T> for $point
+        where
+            $point: Add<T, Output = $point>,
+        {
+            fn add_assign(&mut self, other: T) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: $point) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+point_ops!(Point2 { x, y });
+point_ops!(Point3 { x, y, z });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dirs() {
+        assert_eq!(Dir4::Right, Dir4::Up.turn_right());
+        assert_eq!(Dir4::Left, Dir4::Up.turn_left());
+        assert_eq!(Dir4::Up, Dir4::Down.reverse());
+        assert_eq!(Some(Dir4::Down), Dir4::from_char('v'));
+        assert_eq!((4, 2), Dir4::Down.step((3, 2)));
+
+        assert_eq!(Dir8::UpLeft, Dir8::Up.turn_left());
+        assert_eq!(Dir8::DownLeft, Dir8::UpRight.reverse());
+        assert_eq!(Dir8::Left, Dir4::Left.into());
+        assert_eq!(
+            Point2::ORIGIN,
+            Dir8::ALL
+                .iter()
+                .map(|d| d.offset())
+                .fold(Point2::ORIGIN, Add::add)
+        );
+    }
+
+    #[test]
+    fn test_dir_set() {
+        let mut set = DirSet::EMPTY;
+        assert!(set.insert(Dir4::Left));
+        assert!(set.insert(Dir4::Up));
+        assert!(!set.insert(Dir4::Left));
+        assert_eq!(2, set.len());
+        assert_eq!(vec![Dir4::Up, Dir4::Left], set.iter().collect::<Vec<_>>());
+        set.remove(Dir4::Up);
+        assert_eq!(set, [Dir4::Left].into_iter().collect());
+    }
+
+    #[test]
+    fn test_points() {
+        let mut p = Point2::new(1, 2);
+        p += Dir4::Up;
+        p += Point2::new(2, 2) * 3;
+        assert_eq!(Point2::new(7, 7), p);
+        assert_eq!(Point2::new(-7, -7), -p);
+        assert_eq!(14, p.manhattan(Point2::ORIGIN));
+
+        let q = Point3::new(1, -2, 3) - Point3::from((2, 2, 2));
+        assert_eq!(Point3::new(-1, -4, 1), q);
+        assert_eq!(6, q.manhattan(Point3::ORIGIN));
+    }
+}
@@ -0,0 +1,291 @@
+//! A dense, rectangular grid of cells, as parsed from most grid puzzles.
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::SolveError;
+
+/// A position in a grid, as row and column. Positions outside of the grid
+/// are valid values, e.g. the neighbors of a cell on the edge, but only
+/// [`Grid::get_wrapping`] finds a cell for them.
+pub type Pos = (i32, i32);
+
+const OFFSETS4: [Pos; 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS8: [Pos; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// The cells are stored row by row in a single vector.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Parses a grid with one row per line. `cell` converts each character,
+    /// and returns `None` for characters which are not allowed. All rows must
+    /// be equally long, and not empty.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, SolveError> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut cols = None;
+        let mut rows = 0;
+        for line in input.lines() {
+            let row_start = cells.len();
+            for (i, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| {
+                    SolveError::parse_at(input, &line[i..], format!("unexpected '{c}'"))
+                })?;
+                cells.push(value);
+            }
+            let len = cells.len() - row_start;
+            if len == 0 {
+                return Err(SolveError::parse_at(input, line, "empty row"));
+            }
+            let cols = *cols.get_or_insert(len);
+            if len != cols {
+                return Err(SolveError::parse_at(
+                    input,
+                    line,
+                    format!("expected {cols} columns, got {len}"),
+                ));
+            }
+            rows += 1;
+        }
+        let cols = cols.ok_or_else(|| SolveError::parse_at(input, input, "empty input"))?;
+        Ok(Grid { cells, rows, cols })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// All cells, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn in_bounds(&self, (row, col): Pos) -> bool {
+        row >= 0 && col >= 0 && (row as usize) < self.rows && (col as usize) < self.cols
+    }
+
+    fn offset(&self, pos: Pos) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| pos.0 as usize * self.cols + pos.1 as usize)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.offset(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.offset(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// The cell at a position of the grid repeated infinitely in all
+    /// directions, as if it was the surface of a torus.
+    pub fn get_wrapping(&self, (row, col): Pos) -> &T {
+        let row = row.rem_euclid(self.rows as i32);
+        let col = col.rem_euclid(self.cols as i32);
+        &self[(row, col)]
+    }
+
+    /// The position of the first cell equal to `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        let i = self.cells.iter().position(|cell| cell == value)?;
+        Some(((i / self.cols) as i32, (i % self.cols) as i32))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols = self.cols as i32;
+        (0..self.rows as i32).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The positions in the grid above, left, right and below `pos`.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, &OFFSETS4)
+    }
+
+    /// The positions in the grid around `pos`, including the diagonals.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos, &OFFSETS8)
+    }
+
+    fn neighbors(
+        &self,
+        (row, col): Pos,
+        offsets: &'static [Pos],
+    ) -> impl Iterator<Item = Pos> + '_ {
+        offsets
+            .iter()
+            .map(move |(dr, dc)| (row + dr, col + dc))
+            .filter(|&pos| self.in_bounds(pos))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.cols, "column {col} out of bounds");
+        self.cells.iter().skip(col).step_by(self.cols)
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).map(|row| self.row(row))
+    }
+
+    /// The grid mirrored along its diagonal: rows become columns.
+    pub fn transposed(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: (0..self.cols)
+                .flat_map(|col| self.column(col).cloned())
+                .collect(),
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = SolveError;
+
+    fn from_str(input: &str) -> Result<Self, SolveError> {
+        Grid::parse(input, Some)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.offset(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!("{pos:?} is outside of the {}x{} grid", self.rows, self.cols),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        match self.offset(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{pos:?} is outside of the {}x{} grid", self.rows, self.cols),
+        }
+    }
+}
+
+/// One line per row, without separators between the cells.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.iter_rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#.S\n..#\n";
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid<char> = EXAMPLE.parse().unwrap();
+        assert_eq!((2, 3), (grid.rows(), grid.cols()));
+        assert_eq!(Some((0, 2)), grid.find(&'S'));
+        assert_eq!('#', grid[(1, 2)]);
+        assert_eq!(None, grid.get((2, 0)));
+        assert_eq!(EXAMPLE, grid.to_string());
+
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 2,
+                column: 1,
+                message: "expected 3 columns, got 2".to_string()
+            }),
+            "#.S\n..\n".parse::<Grid<char>>()
+        );
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 1,
+                column: 2,
+                message: "unexpected 'x'".to_string()
+            }),
+            Grid::parse("1x", |c| c.to_digit(10))
+        );
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 1,
+                column: 1,
+                message: "empty row".to_string()
+            }),
+            "\n".parse::<Grid<char>>()
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid: Grid<char> = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            vec![(0, 0), (0, 2), (1, 1)],
+            grid.neighbors4((0, 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 1), (1, 1), (1, 2)],
+            grid.neighbors8((0, 2)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_views() {
+        let grid: Grid<char> = EXAMPLE.parse().unwrap();
+        assert_eq!(&'S', grid.get_wrapping((-2, 5)));
+        assert_eq!(&'#', grid.get_wrapping((3, -1)));
+        assert_eq!(['.', '.', '#'], grid.row(1));
+        assert_eq!(vec![&'S', &'#'], grid.column(2).collect::<Vec<_>>());
+        assert_eq!("#.\n..\nS#\n", grid.transposed().to_string());
+    }
+}
@@ -0,0 +1,326 @@
+//! Searches over [`SearchState`]s, besides the A* search in [`super::astar`].
+use std::collections::VecDeque;
+
+use hashbrown::hash_map::Entry;
+use hashbrown::{HashMap, HashSet};
+
+use super::astar::{BTreeFrontier, Frontier, SearchState};
+
+/// The lowest cost of every state reachable from the start state, by key.
+/// Goals and heuristics are ignored.
+pub fn dijkstra_all<S: SearchState>(start_state: S) -> HashMap<S::Key, usize> {
+    let mut costs = HashMap::new();
+    let mut frontier = BTreeFrontier::default();
+    costs.insert(start_state.key(), start_state.cost());
+    frontier.push(start_state.cost(), start_state, None);
+
+    while let Some((cost, state)) = frontier.pop() {
+        // Skip entries which were replaced by a cheaper one
+        if costs.get(&state.key()) != Some(&cost) {
+            continue;
+        }
+        for next in state.next_states() {
+            let next_cost = next.cost();
+            match costs.entry(next.key()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(next_cost);
+                    frontier.push(next_cost, next, None);
+                }
+                Entry::Occupied(mut entry) if next_cost < *entry.get() => {
+                    let old_cost = entry.insert(next_cost);
+                    frontier.push(next_cost, next, Some(old_cost));
+                }
+                Entry::Occupied(_) => {}
+            }
+        }
+    }
+    costs
+}
+
+/// Visits the states reachable in at most `max_depth` steps, layer by layer.
+/// Each key is visited once, at the depth it is first reached, and `visit`
+/// is called with that depth. Costs, goals and heuristics are ignored.
+/// Returns the number of visited states.
+pub fn bfs_layers<S: SearchState>(
+    start_state: S,
+    max_depth: usize,
+    mut visit: impl FnMut(usize, &S),
+) -> usize {
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    seen.insert(start_state.key());
+    queue.push_back((0, start_state));
+
+    while let Some((depth, state)) = queue.pop_front() {
+        visit(depth, &state);
+        if depth == max_depth {
+            continue;
+        }
+        for next in state.next_states() {
+            if seen.insert(next.key()) {
+                queue.push_back((depth + 1, next));
+            }
+        }
+    }
+    seen.len()
+}
+
+/// Iterative deepening A*: depth-first searches bounded by cost plus
+/// heuristic, raising the bound until a goal is found. Uses little memory,
+/// at the price of expanding states again. The heuristic must not
+/// overestimate for the goal to be the cheapest one.
+pub fn ida_star<S: SearchState + Clone>(start_state: S) -> Option<S> {
+    let mut bound = start_state.cost() + start_state.heuristic();
+    let mut path = Vec::new();
+    loop {
+        match bounded_dfs(start_state.clone(), bound, &mut path) {
+            Bounded::Found(goal) => return Some(goal),
+            Bounded::Exceeded(usize::MAX) => return None,
+            Bounded::Exceeded(next_bound) => bound = next_bound,
+        }
+    }
+}
+
+enum Bounded<S> {
+    Found(S),
+    // The lowest total above the bound, usize::MAX if there was none.
+    Exceeded(usize),
+}
+
+fn bounded_dfs<S: SearchState>(state: S, bound: usize, path: &mut Vec<S::Key>) -> Bounded<S> {
+    let total = state.cost() + state.heuristic();
+    if total > bound {
+        return Bounded::Exceeded(total);
+    } else if state.is_goal() {
+        return Bounded::Found(state);
+    }
+
+    let mut lowest = usize::MAX;
+    path.push(state.key());
+    for next in state.next_states() {
+        // Don't walk in circles
+        if path.contains(&next.key()) {
+            continue;
+        }
+        match bounded_dfs(next, bound, path) {
+            Bounded::Found(goal) => {
+                path.pop();
+                return Bounded::Found(goal);
+            }
+            Bounded::Exceeded(total) => lowest = lowest.min(total),
+        }
+    }
+    path.pop();
+    Bounded::Exceeded(lowest)
+}
+
+/// The cost of the cheapest path between two states, searching from both
+/// ends until the searches meet. The states of `goal_state` walk backwards:
+/// their next states are the ones leading to them, with the cost counted
+/// from the goal. For undirected graphs this is the same type as the start
+/// state. Goals and heuristics are ignored.
+pub fn bidirectional<F, B>(start_state: F, goal_state: B) -> Option<usize>
+where
+    F: SearchState,
+    B: SearchState<Key = F::Key>,
+{
+    let mut forward = Side::new(start_state);
+    let mut backward = Side::new(goal_state);
+    let mut best = forward.meet(&backward);
+
+    // The costs popped last are lower bounds for the rest of each side.
+    while !forward.frontier.is_empty() || !backward.frontier.is_empty() {
+        let expand_forward = backward.frontier.is_empty()
+            || (!forward.frontier.is_empty() && forward.frontier.len() <= backward.frontier.len());
+        let reached = if expand_forward {
+            forward.expand(&backward)
+        } else {
+            backward.expand(&forward)
+        };
+        if let Some(cost) = reached {
+            best = Some(best.map_or(cost, |best| best.min(cost)));
+        }
+        if best.is_some_and(|best| forward.popped + backward.popped >= best) {
+            break;
+        }
+    }
+    best
+}
+
+struct Side<S: SearchState> {
+    frontier: BTreeFrontier<S>,
+    costs: HashMap<S::Key, usize>,
+    popped: usize,
+}
+
+impl<S: SearchState> Side<S> {
+    fn new(state: S) -> Self {
+        let mut frontier = BTreeFrontier::default();
+        let costs = HashMap::from([(state.key(), state.cost())]);
+        frontier.push(state.cost(), state, None);
+        Side {
+            frontier,
+            costs,
+            popped: 0,
+        }
+    }
+
+    /// The cost of a path through a state reached by both sides.
+    fn meet<T: SearchState<Key = S::Key>>(&self, other: &Side<T>) -> Option<usize> {
+        self.costs
+            .iter()
+            .filter_map(|(key, cost)| other.costs.get(key).map(|other| cost + other))
+            .min()
+    }
+
+    /// Expands the cheapest state of this side. Returns the cost of the
+    /// cheapest path found through the states it reached, if they meet the
+    /// other side.
+    fn expand<T: SearchState<Key = S::Key>>(&mut self, other: &Side<T>) -> Option<usize> {
+        let (cost, state) = self.frontier.pop()?;
+        self.popped = cost;
+        if self.costs.get(&state.key()) != Some(&cost) {
+            return None;
+        }
+        let mut reached = None;
+        for next in state.next_states() {
+            let (key, next_cost) = (next.key(), next.cost());
+            let old_cost = self.costs.get(&key).copied();
+            if old_cost.is_some_and(|old_cost| old_cost <= next_cost) {
+                continue;
+            }
+            if let Some(other_cost) = other.costs.get(&key) {
+                let total = next_cost + other_cost;
+                reached = Some(reached.map_or(total, |r: usize| r.min(total)));
+            }
+            self.costs.insert(key, next_cost);
+            self.frontier.push(next_cost, next, old_cost);
+        }
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A walk on a line of positions, with the cost of entering each one.
+    // Only the given positions can be entered.
+    #[derive(Clone)]
+    struct Walk<'a> {
+        costs: &'a [usize],
+        pos: usize,
+        cost: usize,
+        goal: usize,
+    }
+
+    impl SearchState for Walk<'_> {
+        type Key = usize;
+        type Iter = std::vec::IntoIter<Self>;
+
+        fn key(&self) -> usize {
+            self.pos
+        }
+        fn is_goal(&self) -> bool {
+            self.pos == self.goal
+        }
+        fn cost(&self) -> usize {
+            self.cost
+        }
+        fn heuristic(&self) -> usize {
+            self.pos.abs_diff(self.goal)
+        }
+        fn next_states(self) -> Self::Iter {
+            // Steps of one or two positions, in both directions
+            [
+                self.pos + 1,
+                self.pos + 2,
+                self.pos.wrapping_sub(1),
+                self.pos.wrapping_sub(2),
+            ]
+            .into_iter()
+            .filter(|&pos| pos < self.costs.len())
+            .map(|pos| Walk {
+                pos,
+                cost: self.cost + self.costs[pos],
+                ..self
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+        }
+    }
+
+    const COSTS: [usize; 7] = [1, 1, 9, 1, 9, 1, 5];
+
+    fn walk(pos: usize, goal: usize) -> Walk<'static> {
+        Walk {
+            costs: &COSTS,
+            pos,
+            cost: 0,
+            goal,
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_all() {
+        let costs = dijkstra_all(walk(0, 0));
+        assert_eq!(7, costs.len());
+        assert_eq!(Some(&2), costs.get(&3));
+        assert_eq!(Some(&8), costs.get(&6));
+    }
+
+    #[test]
+    fn test_bfs_layers() {
+        let mut layers = Vec::new();
+        let visited = bfs_layers(walk(0, 0), 2, |depth, state| {
+            layers.push((depth, state.pos))
+        });
+        assert_eq!(5, visited);
+        assert_eq!(vec![(0, 0), (1, 1), (1, 2), (2, 3), (2, 4)], layers);
+    }
+
+    #[test]
+    fn test_ida_star() {
+        assert_eq!(Some(8), ida_star(walk(0, 6)).map(|s| s.cost));
+    }
+
+    #[test]
+    fn test_bidirectional() {
+        // Walking backwards, the cost of a step is that of the position left.
+        #[derive(Clone)]
+        struct Back(Walk<'static>);
+        impl SearchState for Back {
+            type Key = usize;
+            type Iter = std::vec::IntoIter<Self>;
+
+            fn key(&self) -> usize {
+                self.0.pos
+            }
+            fn is_goal(&self) -> bool {
+                false
+            }
+            fn cost(&self) -> usize {
+                self.0.cost
+            }
+            fn heuristic(&self) -> usize {
+                0
+            }
+            fn next_states(self) -> Self::Iter {
+                let left = COSTS[self.0.pos];
+                self.0
+                    .next_states()
+                    .map(|w| {
+                        Back(Walk {
+                            cost: w.cost - COSTS[w.pos] + left,
+                            ..w
+                        })
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter()
+            }
+        }
+
+        assert_eq!(Some(8), bidirectional(walk(0, 6), Back(walk(6, 0))));
+        assert_eq!(Some(0), bidirectional(walk(3, 3), Back(walk(3, 3))));
+    }
+}
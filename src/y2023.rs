@@ -23,3 +23,32 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+
+crate::solvers! {
+    year: 2023;
+    1 => day01, "Trebuchet?!";
+    2 => day02, "Cube Conundrum";
+    3 => day03, "Gear Ratios";
+    4 => day04, "Scratchcards";
+    5 => day05, "If You Give A Seed A Fertilizer";
+    6 => day06, "Wait For It";
+    7 => day07, "Camel Cards";
+    8 => day08, "Haunted Wasteland";
+    9 => day09, "Mirage Maintenance";
+    10 => day10, "Pipe Maze";
+    11 => day11, "Cosmic Expansion";
+    12 => day12, "Hot Springs";
+    13 => day13, "Point of Incidence";
+    14 => day14, "Parabolic Reflector Dish";
+    15 => day15, "Lens Library";
+    16 => day16, "The Floor Will Be Lava";
+    17 => day17, "Clumsy Crucible";
+    18 => day18, "Lavaduct Lagoon";
+    19 => day19, "Aplenty";
+    20 => day20, "Pulse Propagation";
+    21 => day21, "Step Counter";
+    22 => day22, "Sand Slabs", disabled;
+    23 => day23, "A Long Walk", disabled;
+    24 => day24, "Never Tell Me The Odds", disabled;
+    25 => day25, "Snowverload";
+}
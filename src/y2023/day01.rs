@@ -1,34 +1,34 @@
 type IsDigitFun = fn(&[u8], usize) -> Option<i32>;
-use crate::Solver;
+use crate::{SolveError, Solver};
 
 pub struct Solution;
 
 impl Solver<i32, i32> for Solution {
-    fn solve(&self, input: &str) -> (i32, i32) {
+    fn solve(&self, input: &str) -> Result<(i32, i32), SolveError> {
         solve(input)
     }
 }
 
-pub fn solve(input: &str) -> (i32, i32) {
+pub fn solve(input: &str) -> Result<(i32, i32), SolveError> {
     input
         .lines()
-        .map(|line| line.as_bytes())
         .filter(|s| !s.is_empty())
-        .fold((0, 0), |(p1, p2), line| {
-            (
-                p1 + get_first_last(line, is_digit1),
-                p2 + get_first_last(line, is_digit2),
-            )
+        .try_fold((0, 0), |(p1, p2), line| {
+            let bytes = line.as_bytes();
+            match (
+                get_first_last(bytes, is_digit1),
+                get_first_last(bytes, is_digit2),
+            ) {
+                (Some(d1), Some(d2)) => Ok((p1 + d1, p2 + d2)),
+                _ => Err(SolveError::parse_at(input, line, "line contains no digits")),
+            }
         })
 }
 
-fn get_first_last(line: &[u8], is_digit: IsDigitFun) -> i32 {
-    let first = (0..line.len()).find_map(|i| is_digit(line, i)).unwrap();
-    let last = (0..line.len())
-        .rev()
-        .find_map(|i| is_digit(line, i))
-        .unwrap();
-    first * 10 + last
+fn get_first_last(line: &[u8], is_digit: IsDigitFun) -> Option<i32> {
+    let first = (0..line.len()).find_map(|i| is_digit(line, i))?;
+    let last = (0..line.len()).rev().find_map(|i| is_digit(line, i))?;
+    Some(first * 10 + last)
 }
 
 fn is_digit1(line: &[u8], i: usize) -> Option<i32> {
@@ -1,10 +1,10 @@
 use itertools::Itertools;
 use lazy_regex::regex;
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 pub struct Solution;
 impl Solver<i32, i32> for Solution {
-    fn solve(&self, input: &str) -> (i32, i32) {
+    fn solve(&self, input: &str) -> Result<(i32, i32), SolveError> {
         solve(input)
     }
 }
@@ -13,24 +13,28 @@ const RED: i32 = 12;
 const GREEN: i32 = 13;
 const BLUE: i32 = 14;
 
-pub fn solve(input: &str) -> (i32, i32) {
+pub fn solve(input: &str) -> Result<(i32, i32), SolveError> {
     // A bit for fun, solve both p1 and p2 in a single statement
     input
         .split('\n')
         .filter(|s| !s.is_empty())
-        .fold((0, 0), |(p1, p2), line| {
-            let (s1, s2) = line.split(':').next_tuple().unwrap();
-            let game = s1[5..].parse::<i32>().unwrap();
+        .try_fold((0, 0), |(p1, p2), line| {
+            let (s1, s2) = line
+                .split(':')
+                .next_tuple()
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected 'Game <n>: ...'"))?;
+            let game = parse_token::<i32>(input, s1.get(5..).unwrap_or(s1))?;
 
             // Note that we do not need to care about the different "sets" drawn
             // from the bag; we can just look at each draw of colored balls on
             // its own.
-            let (game_id, (r, g, b)) = regex!(r"\d+ [rgb]").find_iter(s2).fold(
+            let (game_id, (r, g, b)) = regex!(r"\d+ [rgb]").find_iter(s2).try_fold(
                 (Some(game), (0, 0, 0)),
                 |(game_id, (r, g, b)), m| {
+                    // The regex guarantees a space and a number
                     let (numstr, color) = m.as_str().split_once(' ').unwrap();
-                    let num = numstr.parse::<i32>().unwrap();
-                    (
+                    let num = parse_token::<i32>(input, numstr)?;
+                    Ok::<_, SolveError>((
                         // accumulate part 1 result
                         if game_id.is_some() {
                             match color {
@@ -49,10 +53,10 @@ pub fn solve(input: &str) -> (i32, i32) {
                             "b" => (r, g, b.max(num)),
                             _ => unreachable!(),
                         },
-                    )
+                    ))
                 },
-            );
+            )?;
 
-            (p1 + game_id.unwrap_or(0), r * g * b + p2)
+            Ok((p1 + game_id.unwrap_or(0), r * g * b + p2))
         })
 }
@@ -1,10 +1,10 @@
 use hashbrown::{HashMap, HashSet};
 use itertools::{self, Itertools};
 
-use crate::Solver;
+use crate::{SolveError, Solver};
 pub struct Solution;
 impl Solver<i32, i32> for Solution {
-    fn solve(&self, input: &str) -> (i32, i32) {
+    fn solve(&self, input: &str) -> Result<(i32, i32), SolveError> {
         solve(input)
     }
 }
@@ -13,9 +13,13 @@ fn is_digit(c: &u8) -> bool {
     *c >= b'0' && *c <= b'9'
 }
 
-pub fn solve(input: &str) -> (i32, i32) {
+pub fn solve(input: &str) -> Result<(i32, i32), SolveError> {
     let bytes = input.as_bytes();
-    let w: i32 = bytes.iter().position(|&c| c == b'\n').unwrap() as i32 + 1; // include newline
+    let w: i32 =
+        bytes.iter().position(|&c| c == b'\n').ok_or_else(|| {
+            SolveError::parse_at(input, input, "expected a newline-terminated grid")
+        })? as i32
+            + 1; // include newline
 
     let mut map: HashMap<(usize, char), HashSet<(usize, i32)>> = HashMap::new();
     let mut numbers: Vec<(usize, usize, i32)> = Vec::new();
@@ -97,5 +101,5 @@ pub fn solve(input: &str) -> (i32, i32) {
             }
         });
 
-    (p1, p2)
+    Ok((p1, p2))
 }
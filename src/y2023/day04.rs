@@ -1,22 +1,27 @@
 use hashbrown::{HashMap, HashSet};
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 pub struct Solution;
 impl Solver<i32, i32> for Solution {
-    fn solve(&self, input: &str) -> (i32, i32) {
+    fn solve(&self, input: &str) -> Result<(i32, i32), SolveError> {
         solve(input)
     }
 }
 
-pub fn solve(input: &str) -> (i32, i32) {
+pub fn solve(input: &str) -> Result<(i32, i32), SolveError> {
     let mut map: HashMap<i32, i32> = HashMap::new();
 
-    let (num_cards, sum) = input.lines().filter_map(|line| line.split_once(':')).fold(
-        (0, 0),
-        |(n, sum), (left, right)| {
-            let card_num = left[4..].trim().parse::<i32>().unwrap();
-            let (s1, s2) = right.split_once('|').unwrap();
-            let num_matching = split_nums(s1).intersection(&split_nums(s2)).count() as i32;
+    let (num_cards, sum) = input
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .try_fold((0, 0), |(n, sum), (left, right)| {
+            let card_num = parse_token::<i32>(input, left.get(4..).unwrap_or(left).trim())?;
+            let (s1, s2) = right
+                .split_once('|')
+                .ok_or_else(|| SolveError::parse_at(input, right, "expected '|'"))?;
+            let num_matching = split_nums(input, s1)?
+                .intersection(&split_nums(input, s2)?)
+                .count() as i32;
 
             for i in (card_num + 1)..=(card_num + num_matching) {
                 *map.entry(i).or_insert(0) += map.get(&card_num).unwrap_or(&0) + 1;
@@ -29,17 +34,16 @@ pub fn solve(input: &str) -> (i32, i32) {
                     1 << (num_matching - 1)
                 };
 
-            (n + 1, sum0)
-        },
-    );
+            Ok::<_, SolveError>((n + 1, sum0))
+        })?;
 
-    (sum, (map.values().sum::<i32>() + num_cards))
+    Ok((sum, (map.values().sum::<i32>() + num_cards)))
 }
 
-fn split_nums(s: &str) -> HashSet<i32> {
+fn split_nums(input: &str, s: &str) -> Result<HashSet<i32>, SolveError> {
     s.split(' ')
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
-        .map(|s| s.parse().unwrap())
+        .map(|s| parse_token(input, s))
         .collect()
 }
@@ -4,28 +4,33 @@ use std::collections::VecDeque;
 
 use itertools::Itertools;
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 
 type Steps = Vec<Vec<(i64, i64, i64)>>;
 
 pub struct Solution;
 impl Solver<i64, i64> for Solution {
-    fn solve(&self, input: &str) -> (i64, i64) {
+    fn solve(&self, input: &str) -> Result<(i64, i64), SolveError> {
         solve(input)
     }
 }
 
-fn solve(input: &str) -> (i64, i64) {
-    let (header, rest) = input.split_once("\n\n").unwrap();
-    let sections = parse_sections(rest);
-    let p1 = solve_p1(header, &sections);
-    let p2 = solve_p2(header, &sections);
-    (p1, p2)
+pub fn solve(input: &str) -> Result<(i64, i64), SolveError> {
+    let (header, rest) = input
+        .split_once("\n\n")
+        .ok_or_else(|| SolveError::parse_at(input, input, "expected seeds and map sections"))?;
+    let seeds = parse_header(input, header)?;
+    if seeds.is_empty() {
+        return Err(SolveError::parse_at(input, header, "no seeds"));
+    }
+    let sections = parse_sections(input, rest)?;
+    let p1 = solve_p1(seeds.clone(), &sections)?;
+    let p2 = solve_p2(&seeds, &sections)?;
+    Ok((p1, p2))
 }
 
-fn solve_p1(header: &str, steps: &Steps) -> i64 {
-    let mut seeds = parse_header(header);
-    *seeds
+fn solve_p1(mut seeds: Vec<i64>, steps: &Steps) -> Result<i64, SolveError> {
+    seeds
         .iter_mut()
         .map(|s| {
             for step in steps {
@@ -40,11 +45,12 @@ fn solve_p1(header: &str, steps: &Steps) -> i64 {
             s
         })
         .min()
-        .unwrap()
+        .copied()
+        .ok_or_else(|| SolveError::solve("no seeds"))
 }
 
-fn solve_p2(header: &str, steps: &Steps) -> i64 {
-    let mut cur: VecDeque<(i64, i64)> = parse_header(header)
+fn solve_p2(seeds: &[i64], steps: &Steps) -> Result<i64, SolveError> {
+    let mut cur: VecDeque<(i64, i64)> = seeds
         .iter()
         .tuples::<(_, _)>()
         .map(|(a, b)| (*a, a + b - 1))
@@ -95,18 +101,21 @@ fn solve_p2(header: &str, steps: &Steps) -> i64 {
         cur = new;
     }
 
-    *cur.iter().map(|(a, _)| a).min().unwrap()
+    cur.iter()
+        .map(|(a, _)| *a)
+        .min()
+        .ok_or_else(|| SolveError::solve("no seed ranges, the seeds must come in pairs"))
 }
 
-fn parse_header(header: &str) -> Vec<i64> {
+fn parse_header(input: &str, header: &str) -> Result<Vec<i64>, SolveError> {
     header
         .split(' ')
         .skip(1)
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect_vec()
+        .map(|s| parse_token(input, s))
+        .collect()
 }
 
-fn parse_sections(sections: &str) -> Steps {
+fn parse_sections(input: &str, sections: &str) -> Result<Steps, SolveError> {
     sections
         .split("\n\n")
         .map(|sec| {
@@ -115,15 +124,32 @@ fn parse_sections(sections: &str) -> Steps {
                 .filter(|s| !s.is_empty())
                 .map(|line| {
                     line.split(' ')
-                        .map(|s| s.parse::<i64>().unwrap())
+                        .map(|s| parse_token(input, s))
+                        .collect::<Result<Vec<i64>, _>>()?
+                        .into_iter()
                         .collect_tuple::<(_, _, _)>()
-                        .unwrap()
+                        .ok_or_else(|| SolveError::parse_at(input, line, "expected three numbers"))
                 })
-                .collect_vec()
+                .collect()
         })
-        .collect_vec()
+        .collect()
 }
 
 fn overlap(a: i64, b: i64, c: i64, d: i64) -> bool {
     !(a > d || b < c)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_seed_ranges() {
+        assert_eq!(
+            Err(SolveError::solve(
+                "no seed ranges, the seeds must come in pairs"
+            )),
+            solve("seeds: 79\n\nseed-to-soil map:\n50 98 2\n")
+        );
+    }
+}
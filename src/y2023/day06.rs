@@ -1,34 +1,45 @@
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 pub struct Solution;
 impl Solver<u64, u64> for Solution {
-    fn solve(&self, input: &str) -> (u64, u64) {
+    fn solve(&self, input: &str) -> Result<(u64, u64), SolveError> {
         let mut lines = input.lines();
-        let times = lines.next().unwrap();
-        let dists = lines.next().unwrap();
-        (solve_p1(times, dists), solve_p2(times, dists))
+        let (times, dists) = lines
+            .next()
+            .zip(lines.next())
+            .ok_or_else(|| SolveError::parse_at(input, input, "expected two lines"))?;
+        Ok((
+            solve_p1(input, times, dists)?,
+            solve_p2(input, times, dists)?,
+        ))
     }
 }
 
-fn solve_p1(times: &str, dists: &str) -> u64 {
+fn solve_p1(input: &str, times: &str, dists: &str) -> Result<u64, SolveError> {
     times
         .split_whitespace()
         .zip(dists.split_whitespace())
         .skip(1)
-        .map(|(t, d)| (t.parse().unwrap(), d.parse().unwrap()))
-        .fold(1, |acc, (time, dist)| acc * find_holdtime(time, dist))
+        .try_fold(1, |acc, (t, d)| {
+            Ok(acc * find_holdtime(parse_token(input, t)?, parse_token(input, d)?))
+        })
 }
 
-fn solve_p2(times: &str, dists: &str) -> u64 {
-    let time = parse_number_with_spaces(&times[10..]);
-    let dist = parse_number_with_spaces(&dists[10..]);
-    find_holdtime(time, dist)
+fn solve_p2(input: &str, times: &str, dists: &str) -> Result<u64, SolveError> {
+    let time = parse_number_with_spaces(input, times)?;
+    let dist = parse_number_with_spaces(input, dists)?;
+    Ok(find_holdtime(time, dist))
 }
 
-fn parse_number_with_spaces(input: &str) -> u64 {
-    input
-        .bytes()
-        .filter(|&c| c != b' ')
-        .fold(0, |acc, x| acc * 10 + (x - b'0') as u64)
+/// Parses the numbers after the label on a line as one number, ignoring spaces
+fn parse_number_with_spaces(input: &str, line: &str) -> Result<u64, SolveError> {
+    let digits = line.get(10..).unwrap_or_default();
+    digits.bytes().filter(|&c| c != b' ').try_fold(0, |acc, x| {
+        if x.is_ascii_digit() {
+            Ok(acc * 10 + (x - b'0') as u64)
+        } else {
+            Err(SolveError::parse_at(input, digits, "expected digits"))
+        }
+    })
 }
 
 fn find_holdtime(time: u64, distance: u64) -> u64 {
@@ -1,4 +1,4 @@
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 use hashbrown::HashMap;
 use itertools::Itertools;
 pub struct Solution;
@@ -15,23 +15,23 @@ enum HandType {
 }
 
 impl Solver<usize, usize> for Solution {
-    fn solve(&self, input: &str) -> (usize, usize) {
-        (
+    fn solve(&self, input: &str) -> Result<(usize, usize), SolveError> {
+        Ok((
             find_total_winnings(
                 input,
                 &classify_hand,
                 vec![
                     'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
                 ],
-            ),
+            )?,
             find_total_winnings(
                 input,
                 &classify_hand_with_jokers,
                 vec![
                     'A', 'K', 'Q', 'T', '9', '8', '7', '6', '5', '4', '3', '2', 'J',
                 ],
-            ),
-        )
+            )?,
+        ))
     }
 }
 
@@ -39,7 +39,7 @@ fn find_total_winnings(
     input: &str,
     classify_fun: &dyn Fn(Vec<char>) -> HandType,
     card_order: Vec<char>,
-) -> usize {
+) -> Result<usize, SolveError> {
     let sort_keys = card_order
         .iter()
         .rev()
@@ -50,28 +50,37 @@ fn find_total_winnings(
     let mut hands = input
         .trim()
         .lines()
-        .map(|line| line.split_once(' ').unwrap())
-        .map(|(hand, bid)| {
-            (
+        .map(|line| {
+            let (hand, bid) = line
+                .split_once(' ')
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected '<hand> <bid>'"))?;
+            // Validate the hand before classifying it
+            let key = sort_key(hand, &sort_keys)
+                .ok_or_else(|| SolveError::parse_at(input, hand, "expected five cards"))?;
+            Ok((
                 classify_fun(hand.chars().collect_vec()),
-                sort_key(hand, &sort_keys),
-                bid.parse::<usize>().unwrap(),
-            )
+                key,
+                parse_token::<usize>(input, bid)?,
+            ))
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, SolveError>>()?;
 
     hands.sort();
 
-    (1..=hands.len())
+    Ok((1..=hands.len())
         .zip(hands)
-        .fold(0, |acc, (rank, (_, _, bid))| rank * bid + acc)
+        .fold(0, |acc, (rank, (_, _, bid))| rank * bid + acc))
 }
 
-fn sort_key(hand: &str, sort_keys: &HashMap<char, usize>) -> (usize, usize, usize, usize, usize) {
+fn sort_key(
+    hand: &str,
+    sort_keys: &HashMap<char, usize>,
+) -> Option<(usize, usize, usize, usize, usize)> {
     hand.chars()
-        .map(|card| *sort_keys.get(&card).unwrap())
+        .map(|card| sort_keys.get(&card).copied())
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
         .collect_tuple()
-        .unwrap()
 }
 
 fn classify_hand(hand: Vec<char>) -> HandType {
@@ -1,40 +1,61 @@
 use hashbrown::HashMap;
-use itertools::{
-    FoldWhile::{Continue, Done},
-    Itertools,
-};
-
 use rayon::{
     self,
     iter::{IntoParallelIterator, ParallelBridge, ParallelIterator},
 };
 
-use crate::Solver;
+use crate::{SolveError, Solver};
 pub struct Solution;
 impl Solver<i64, i64> for Solution {
-    fn solve(&self, input: &str) -> (i64, i64) {
-        let (line1, rest) = input.split_once('\n').unwrap();
+    fn solve(&self, input: &str) -> Result<(i64, i64), SolveError> {
+        let (line1, rest) = input
+            .split_once('\n')
+            .ok_or_else(|| SolveError::parse_at(input, input, "expected directions"))?;
 
         let dirs = line1.as_bytes();
+        if dirs.is_empty() {
+            return Err(SolveError::parse_at(input, line1, "expected directions"));
+        } else if let Some(i) = dirs.iter().position(|d| *d != b'L' && *d != b'R') {
+            return Err(SolveError::parse_at(
+                input,
+                &line1[i..],
+                "expected 'L' or 'R'",
+            ));
+        }
+
         let mut map: HashMap<&str, (&str, &str)> = HashMap::new();
 
         for line in rest.trim().split('\n').collect::<Vec<&str>>() {
-            let from = &line[0..3];
-            let left = &line[7..10];
-            let right = &line[12..15];
+            let (from, left, right) = line
+                .get(0..3)
+                .zip(line.get(7..10))
+                .zip(line.get(12..15))
+                .map(|((from, left), right)| (from, left, right))
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected 'AAA = (BBB, CCC)'"))?;
             map.insert(from, (left, right));
         }
 
-        (solve_p1(&map, dirs), solve_p2(&map, dirs))
+        // All nodes we can walk to must exist
+        for line in rest.trim().split('\n') {
+            for node in [&line[7..10], &line[12..15]] {
+                if !map.contains_key(node) {
+                    return Err(SolveError::parse_at(input, node, "unknown node"));
+                }
+            }
+        }
+
+        Ok((solve_p1(&map, dirs)?, solve_p2(input, &map, dirs)?))
     }
 }
 
-fn solve_p1(map: &HashMap<&str, (&str, &str)>, dirs: &[u8]) -> i64 {
+fn solve_p1(map: &HashMap<&str, (&str, &str)>, dirs: &[u8]) -> Result<i64, SolveError> {
     let mut current = "AAA";
     let mut steps = 0;
 
     for i in 0.. {
-        let (left, right) = map.get(current).unwrap();
+        let (left, right) = map
+            .get(current)
+            .ok_or_else(|| SolveError::solve(format!("no node named {current}")))?;
         let d = dirs[i as usize % dirs.len()];
         steps += 1;
         match d {
@@ -48,33 +69,55 @@ fn solve_p1(map: &HashMap<&str, (&str, &str)>, dirs: &[u8]) -> i64 {
         }
     }
 
-    steps
+    Ok(steps)
 }
 
-fn solve_p2(map: &HashMap<&str, (&str, &str)>, dirs: &[u8]) -> i64 {
+fn solve_p2(
+    input: &str,
+    map: &HashMap<&str, (&str, &str)>,
+    dirs: &[u8],
+) -> Result<i64, SolveError> {
     map.keys()
         .par_bridge()
         .into_par_iter()
         .filter(|node| node.ends_with('A'))
         .map(|node| {
-            let (_, count) = dirs
-                .iter()
-                .cycle()
-                .fold_while((node, 0), |(src, count), dir| {
-                    let (left, right) = map.get(src).unwrap();
-                    let dest = match dir {
-                        b'L' => left,
-                        b'R' => right,
-                        _ => unreachable!(),
-                    };
-                    if !dest.ends_with('Z') {
-                        Continue((dest, count + 1))
-                    } else {
-                        Done((dest, count + 1))
-                    }
-                })
-                .into_inner();
-            count
+            let mut src = *node;
+            let mut count = 0;
+            for dir in dirs.iter().cycle() {
+                // Nodes are slices of the input
+                let (left, right) = map
+                    .get(src)
+                    .ok_or_else(|| SolveError::parse_at(input, src, "unknown node"))?;
+                src = match dir {
+                    b'L' => left,
+                    b'R' => right,
+                    _ => unreachable!(),
+                };
+                count += 1;
+                if src.ends_with('Z') {
+                    break;
+                }
+            }
+            Ok(count)
         })
-        .reduce(|| 1, num::integer::lcm)
+        .try_reduce(|| 1, |a, b| Ok(num::integer::lcm(a, b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bad_input() {
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 3,
+                column: 8,
+                message: "unknown node".to_string()
+            }),
+            Solution.solve("LR\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)\n")
+        );
+        assert!(Solution.solve("\n\nAAA = (AAA, ZZZ)\n").is_err());
+    }
 }
@@ -1,22 +1,22 @@
 use itertools::Itertools;
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 pub struct Solution;
 impl Solver<i64, i64> for Solution {
-    fn solve(&self, input: &str) -> (i64, i64) {
-        let input = input
+    fn solve(&self, input: &str) -> Result<(i64, i64), SolveError> {
+        let seqs = input
             .lines()
             .map(|line| {
                 line.split_ascii_whitespace()
-                    .map(|s| s.parse::<i64>().unwrap())
-                    .collect_vec()
+                    .map(|s| parse_token::<i64>(input, s))
+                    .collect::<Result<Vec<_>, _>>()
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        (
-            do_solve(&input, &predict_next),
-            do_solve(&input, &predict_prev),
-        )
+        Ok((
+            do_solve(&seqs, &predict_next),
+            do_solve(&seqs, &predict_prev),
+        ))
     }
 }
 
@@ -1,165 +1,132 @@
-use crate::Solver;
-use hashbrown::{HashMap, HashSet};
+use crate::{
+    utils::{
+        geom::Dir4,
+        grid::{Grid, Pos},
+    },
+    SolveError, Solver,
+};
 pub struct Solution;
 impl Solver<i32, i32> for Solution {
-    fn solve(&self, input: &str) -> (i32, i32) {
+    fn solve(&self, input: &str) -> Result<(i32, i32), SolveError> {
         solve(input)
     }
 }
 
-type RowCol = (i32, i32);
-
-pub fn solve(input: &str) -> (i32, i32) {
-    let mut map: HashMap<RowCol, char> = HashMap::new();
-    let mut start: RowCol = (0, 0);
-    let mut maxrow = 0;
-    let mut maxcol = 0;
-    for (row, line) in input.lines().enumerate() {
-        for (col, char) in line.chars().enumerate() {
-            // To allow for tile paths to "sneak" in between pipes, we expand
-            // the grid by a factor of 2. We can then compute the main loop
-            // using only even coordinates, and do a flood fill using all
-            // coordinates.
-            let rowcol = (row as i32 * 2, col as i32 * 2);
-            if char == 'S' {
-                start = rowcol;
-            }
-            map.insert(rowcol, char);
-            maxrow = maxrow.max(rowcol.0);
-            maxcol = maxcol.max(rowcol.1);
-        }
-    }
-
-    let (p1, mainloop) = solve_p1(&start, &map);
+pub fn solve(input: &str) -> Result<(i32, i32), SolveError> {
+    let map = Grid::parse(input, |c| "|-LJ7F.S".contains(c).then_some(c))?;
+    let start = map
+        .find(&'S')
+        .ok_or_else(|| SolveError::parse_at(input, input, "no start position ('S')"))?;
+    let (p1, mainloop) = solve_p1(start, &map)?;
+    let p2 = solve_p2(&map, &mainloop);
 
-    // The +2 here is to compensate for the extra space in the grid
-    let p2 = solve_p2(&(0, 0), &(maxrow + 2, maxcol + 2), &mainloop);
+    Ok((p1, p2))
+}
 
-    (p1, p2)
+// To allow for tile paths to "sneak" in between pipes, we expand the grid by
+// a factor of 2, with an empty border around it. Tiles are at odd
+// coordinates, and the main loop also covers the cells between its pipes.
+fn expanded((row, col): Pos) -> Pos {
+    (row * 2 + 1, col * 2 + 1)
 }
 
-fn solve_p1(start: &RowCol, map: &HashMap<RowCol, char>) -> (i32, HashSet<RowCol>) {
-    let mut mainloop: HashSet<RowCol> = HashSet::new();
-    let mut prev = *start;
-    let (pipe1, _) = connecting_pipes(start, map);
-    let mut curr = pipe1;
+fn solve_p1(start: Pos, map: &Grid<char>) -> Result<(i32, Grid<bool>), SolveError> {
+    let mut mainloop = Grid::new(map.rows() * 2 + 1, map.cols() * 2 + 1, false);
+    let mut prev = start;
+    let (mut curr, _) = connecting_pipes(start, map)?;
     let mut steps = 1;
 
-    mainloop.insert(curr.0);
-    mainloop.insert(curr.1);
-
-    while curr.0 != *start {
-        let (next1, next2) = connecting_pipes(&curr.0, map);
-
-        assert!(next1.0 .0 % 2 == 0);
-        assert!(next2.0 .1 % 2 == 0);
-
-        if next1.0 == prev {
-            prev = curr.0;
-            curr = next2;
-        } else {
-            prev = curr.0;
-            curr = next1;
+    loop {
+        let (a, b) = (expanded(prev), expanded(curr));
+        mainloop[((a.0 + b.0) / 2, (a.1 + b.1) / 2)] = true;
+        mainloop[b] = true;
+        if curr == start {
+            break;
         }
 
-        mainloop.insert(curr.0);
-        mainloop.insert(curr.1);
+        let (next1, next2) = connecting_pipes(curr, map)?;
+        let next = if next1 == prev { next2 } else { next1 };
+        prev = curr;
+        curr = next;
         steps += 1;
     }
 
-    (steps >> 1, mainloop)
+    Ok((steps / 2, mainloop))
 }
 
-fn solve_p2(start: &RowCol, limits: &(i32, i32), mainloop: &HashSet<RowCol>) -> i32 {
-    let mut outer = HashSet::new();
-    let mut edge = Vec::new();
-    let (maxrow, maxcol) = limits;
-    edge.push(*start);
+fn solve_p2(map: &Grid<char>, mainloop: &Grid<bool>) -> i32 {
+    let mut outer = Grid::new(mainloop.rows(), mainloop.cols(), false);
+    let mut edge = vec![(0, 0)];
 
     while let Some(node) = edge.pop() {
-        let (row, col) = node;
-        if mainloop.contains(&node)
-            || (row < 0 || row > *maxrow || col < 0 || col > *maxcol)
-            || outer.contains(&node)
-        {
+        if mainloop[node] || outer[node] {
             continue;
         }
 
-        outer.insert(node);
-
-        edge.push((row - 1, col));
-        edge.push((row + 1, col));
-        edge.push((row, col - 1));
-        edge.push((row, col + 1));
+        outer[node] = true;
+        edge.extend(outer.neighbors4(node));
     }
 
     // Note that we cannot just compute the number of "inner" nodes by
     // using total - (outer + mainloop), since we need to count only
-    // "even" inner nodes.
-    let mut inner = 0;
-    for row in (0..=*maxrow).step_by(2) {
-        for col in (0..=*maxcol).step_by(2) {
-            let rowcol = (row, col);
-            if outer.contains(&rowcol) || mainloop.contains(&rowcol) {
-                continue;
-            } else {
-                inner += 1;
-            }
-        }
-    }
-    inner
+    // the tiles of the original grid.
+    map.positions()
+        .map(expanded)
+        .filter(|&pos| !outer[pos] && !mainloop[pos])
+        .count() as i32
 }
 
-fn connecting_pipes(
-    rowcol: &RowCol,
-    map: &HashMap<RowCol, char>,
-) -> ((RowCol, RowCol), (RowCol, RowCol)) {
-    let (row, col) = rowcol;
-    let c = map.get(rowcol).unwrap();
-
-    let north = (row - 2, *col);
-    let east = (*row, col + 2);
-    let south = (row + 2, *col);
-    let west = (*row, col - 2);
-
-    let north0 = (row - 1, *col);
-    let east0 = (*row, col + 1);
-    let south0 = (row + 1, *col);
-    let west0 = (*row, col - 1);
-
-    let n = map.get(&north).unwrap_or(&' ');
-    let e = map.get(&east).unwrap_or(&' ');
-    let s = map.get(&south).unwrap_or(&' ');
-    let w = map.get(&west).unwrap_or(&' ');
-
-    let mut pipes = Vec::new();
-
-    //   |   |    --7  F--
-    // --J   L--    |  |
-    if (*n == 'F' || *n == '|' || *n == '7' || *n == 'S')
-        && (*c == 'J' || *c == '|' || *c == 'L' || *c == 'S')
-    {
-        pipes.push((north, north0));
-    }
-    if (*e == '7' || *e == '-' || *e == 'J' || *e == 'S')
-        && (*c == 'F' || *c == '-' || *c == 'L' || *c == 'S')
-    {
-        pipes.push((east, east0));
-    }
-    if (*s == 'J' || *s == '|' || *s == 'L' || *s == 'S')
-        && (*c == 'F' || *c == '|' || *c == '7' || *c == 'S')
-    {
-        pipes.push((south, south0));
+/// Whether a pipe has an opening towards `dir`.
+fn connects(pipe: char, dir: Dir4) -> bool {
+    match dir {
+        Dir4::Up => "|LJS".contains(pipe),
+        Dir4::Right => "-LFS".contains(pipe),
+        Dir4::Down => "|7FS".contains(pipe),
+        Dir4::Left => "-J7S".contains(pipe),
     }
+}
 
-    if (*w == 'L' || *w == '-' || *w == 'F' || *w == 'S')
-        && (*c == 'J' || *c == '-' || *c == '7' || *c == 'S')
-    {
-        pipes.push((west, west0));
-    }
+fn connecting_pipes(pos: Pos, map: &Grid<char>) -> Result<(Pos, Pos), SolveError> {
+    let pipes: Vec<Pos> = Dir4::ALL
+        .into_iter()
+        .filter(|&dir| {
+            connects(map[pos], dir)
+                && map
+                    .get(dir.step(pos))
+                    .is_some_and(|&next| connects(next, dir.reverse()))
+        })
+        .map(|dir| dir.step(pos))
+        .collect();
 
     // There should be exactly 2 pipes connecting each pipe segment
-    assert_eq!(2, pipes.len());
+    match pipes[..] {
+        [pipe1, pipe2] => Ok((pipe1, pipe2)),
+        _ => Err(SolveError::solve(format!(
+            "the pipe at line {}, column {} should connect to 2 pipes, not {}",
+            pos.0 + 1,
+            pos.1 + 1,
+            pipes.len()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    (pipes[0], pipes[1])
+    #[test]
+    fn test_loop_on_edge() {
+        // The flood fill has to get around a loop which touches the edges
+        assert_eq!(Ok((8, 1)), solve("7-F7-\n.FJ|7\nSJLL7\n|F--J\nLJ.LJ\n"));
+    }
+
+    #[test]
+    fn test_broken_loop() {
+        assert_eq!(
+            Err(SolveError::solve(
+                "the pipe at line 3, column 4 should connect to 2 pipes, not 1"
+            )),
+            solve(".....\n.S-7.\n.|.|.\n.L-..\n.....\n")
+        );
+    }
 }
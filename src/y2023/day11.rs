@@ -1,11 +1,11 @@
-use crate::Solver;
+use crate::{SolveError, Solver};
 use hashbrown::HashSet;
 
 use itertools::Itertools;
 pub struct Solution;
 impl Solver<usize, usize> for Solution {
-    fn solve(&self, input: &str) -> (usize, usize) {
-        solve(input)
+    fn solve(&self, input: &str) -> Result<(usize, usize), SolveError> {
+        Ok(solve(input))
     }
 }
 
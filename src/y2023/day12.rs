@@ -1,13 +1,12 @@
 use hashbrown::HashMap;
-use itertools::Itertools;
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 pub struct Solution;
 impl Solver<i64, i64> for Solution {
-    fn solve(&self, input: &str) -> (i64, i64) {
-        let p1 = sum_arrangements(input, 1);
-        let p2 = sum_arrangements(input, 5);
-        (p1, p2)
+    fn solve(&self, input: &str) -> Result<(i64, i64), SolveError> {
+        let p1 = sum_arrangements(input, 1)?;
+        let p2 = sum_arrangements(input, 5)?;
+        Ok((p1, p2))
     }
 }
 
@@ -30,26 +29,27 @@ impl Duplicatable for String {
     }
 }
 
-fn sum_arrangements(input: &str, copies: i32) -> i64 {
+fn sum_arrangements(input: &str, copies: i32) -> Result<i64, SolveError> {
     input
         .lines()
-        .map(|line| count_arrangements(line, copies))
+        .map(|line| count_arrangements(input, line, copies))
         .sum()
 }
 
-fn count_arrangements(line: &str, copies: i32) -> i64 {
+fn count_arrangements(input: &str, line: &str, copies: i32) -> Result<i64, SolveError> {
     let mut cache = HashMap::new();
-    let (left, right) = line.split_once(' ').unwrap();
+    let (left, right) = line
+        .split_once(' ')
+        .ok_or_else(|| SolveError::parse_at(input, line, "expected '<springs> <counts>'"))?;
     let counts = right
-        .to_string()
-        .duplicate_with_sep(copies, ',')
         .split(',')
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect_vec();
+        .map(|n| parse_token::<i64>(input, n))
+        .collect::<Result<Vec<_>, _>>()?
+        .repeat(copies as usize);
 
     let record = format!("{}.", left.to_string().duplicate_with_sep(copies, '?'));
 
-    get_count(record.as_bytes(), &counts, 0, 0, 0, &mut cache)
+    Ok(get_count(record.as_bytes(), &counts, 0, 0, 0, &mut cache))
 }
 
 fn get_count(
@@ -1,9 +1,9 @@
-use crate::Solver;
+use crate::{SolveError, Solver};
 use rayon::iter::{IntoParallelRefIterator, ParallelBridge, ParallelIterator};
 
 pub struct Solution;
 impl Solver<usize, usize> for Solution {
-    fn solve(&self, input: &str) -> (usize, usize) {
+    fn solve(&self, input: &str) -> Result<(usize, usize), SolveError> {
         // Solve p1 and p2 in parallel
         let mut solutions = vec![0, 1]
             .par_iter()
@@ -16,7 +16,7 @@ impl Solver<usize, usize> for Solution {
             .collect::<Vec<(usize, usize)>>();
 
         solutions.sort();
-        (solutions[0].1, solutions[1].1)
+        Ok((solutions[0].1, solutions[1].1))
     }
 }
 
@@ -1,73 +1,45 @@
 pub struct Solution;
 impl Solver<usize, usize> for Solution {
-    fn solve(&self, input: &str) -> (usize, usize) {
+    fn solve(&self, input: &str) -> Result<(usize, usize), SolveError> {
         solve(input)
     }
 }
 
-use grid::Grid;
 use hashbrown::HashMap;
-use strum::{EnumIter, IntoEnumIterator};
 
-use crate::Solver;
+use crate::{
+    utils::{geom::Dir4, grid::Grid},
+    SolveError, Solver,
+};
 
 type Platform = Grid<char>;
-#[derive(EnumIter, PartialEq)]
-enum Direction {
-    North,
-    West,
-    South,
-    East,
-}
+// One spin cycle tilts north, then west, south and east
+const CYCLE: [Dir4; 4] = [Dir4::Up, Dir4::Left, Dir4::Down, Dir4::Right];
 
-fn can_move_to(platform: &Platform, row: i32, col: i32) -> bool {
-    row >= 0
-        && row < platform.rows() as i32
-        && col >= 0
-        && col < platform.cols() as i32
-        && platform[(row as usize, col as usize)] == '.'
+fn can_move_to(platform: &Platform, pos: (i32, i32)) -> bool {
+    platform.get(pos) == Some(&'.')
 }
 
-fn tilt(platform: &mut Platform, dir: &Direction) {
-    let rows = platform.rows();
-    let cols = platform.cols();
+fn tilt(platform: &mut Platform, dir: Dir4) {
+    let rows = platform.rows() as i32;
+    let cols = platform.cols() as i32;
     for row in 0..rows {
         for col in 0..cols {
             // invert the row/col when tilting south or east
-            let (row0, col0): (usize, usize) = match dir {
-                Direction::North => (row, col),
-                Direction::West => (row, col),
-                Direction::South => {
-                    let inv_row = ((rows as i32) - (row as i32) - 1) as usize;
-                    (inv_row, col)
-                }
-                Direction::East => {
-                    let inv_col = (cols as i32 - col as i32 - 1) as usize;
-                    (row, inv_col)
-                }
+            let (row0, col0) = match dir {
+                Dir4::Up | Dir4::Left => (row, col),
+                Dir4::Down => (rows - row - 1, col),
+                Dir4::Right => (row, cols - col - 1),
             };
 
             if platform[(row0, col0)] == 'O' {
-                let (delta_row, delta_col) = match dir {
-                    Direction::North => (-1, 0),
-                    Direction::West => (0, -1),
-                    Direction::East => (0, 1),
-                    Direction::South => (1, 0),
-                };
-                let mut next_row = row0 as i32;
-                let mut next_col = col0 as i32;
-                let mut maybe_next_row = next_row + delta_row;
-                let mut maybe_next_col = next_col + delta_col;
-
-                while can_move_to(platform, maybe_next_row, maybe_next_col) {
-                    next_row = maybe_next_row;
-                    next_col = maybe_next_col;
-                    maybe_next_row += delta_row;
-                    maybe_next_col += delta_col;
+                let mut next = (row0, col0);
+                while can_move_to(platform, dir.step(next)) {
+                    next = dir.step(next);
                 }
 
                 platform[(row0, col0)] = '.';
-                platform[(next_row as usize, next_col as usize)] = 'O';
+                platform[next] = 'O';
             }
         }
     }
@@ -75,10 +47,10 @@ fn tilt(platform: &mut Platform, dir: &Direction) {
 
 fn get_load(platform: &Platform) -> usize {
     platform
-        .indexed_iter()
+        .iter()
         .filter_map(|((row, _), c)| {
             if *c == 'O' {
-                Some(platform.rows() - row)
+                Some(platform.rows() - row as usize)
             } else {
                 None
             }
@@ -86,17 +58,9 @@ fn get_load(platform: &Platform) -> usize {
         .sum()
 }
 
-pub fn solve(input: &str) -> (usize, usize) {
+pub fn solve(input: &str) -> Result<(usize, usize), SolveError> {
     let mut cache = HashMap::new();
-    let rows = input.lines().count();
-    let cols = input.lines().next().unwrap().len();
-    let mut platform = Platform::new(rows, cols);
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            platform[(row, col)] = c;
-        }
-    }
+    let mut platform: Platform = input.parse()?;
 
     let mut p1: Option<usize> = None;
     let p2;
@@ -104,9 +68,9 @@ pub fn solve(input: &str) -> (usize, usize) {
     let mut cycle = 1;
     let mut enable_caching = true;
     loop {
-        Direction::iter().for_each(|d| {
-            tilt(&mut platform, &d);
-            if d == Direction::North {
+        CYCLE.into_iter().for_each(|d| {
+            tilt(&mut platform, d);
+            if d == Dir4::Up {
                 // store the first load we find in the north direction as p1 solution
                 p1.get_or_insert_with(|| get_load(&platform));
             }
@@ -118,7 +82,7 @@ pub fn solve(input: &str) -> (usize, usize) {
         }
 
         if enable_caching {
-            let key = platform.clone().into_vec();
+            let key = platform.cells().to_vec();
             if cache.contains_key(&key) {
                 let period_start = cache.get(&key).unwrap();
                 let period_len = cycle - period_start;
@@ -134,5 +98,5 @@ pub fn solve(input: &str) -> (usize, usize) {
         cycle += 1;
     }
 
-    (p1.unwrap(), p2.unwrap())
+    Ok((p1.unwrap(), p2.unwrap()))
 }
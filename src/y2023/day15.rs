@@ -1,31 +1,33 @@
 pub struct Solution;
 impl Solver<i32, usize> for Solution {
-    fn solve(&self, input: &str) -> (i32, usize) {
+    fn solve(&self, input: &str) -> Result<(i32, usize), SolveError> {
         solve(input)
     }
 }
 
 use hashbrown::HashMap;
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 
-pub fn solve(input: &str) -> (i32, usize) {
+pub fn solve(input: &str) -> Result<(i32, usize), SolveError> {
     let p1 = solve_p1(input);
-    let p2 = solve_p2(input);
-    (p1, p2)
+    let p2 = solve_p2(input)?;
+    Ok((p1, p2))
 }
 
 fn solve_p1(input: &str) -> i32 {
     input.split(',').map(hash).sum()
 }
 
-fn solve_p2(input: &str) -> usize {
+fn solve_p2(input: &str) -> Result<usize, SolveError> {
     let mut boxes: Vec<Vec<(String, usize)>> = Vec::new();
     let mut lenses: HashMap<String, (usize, usize)> = HashMap::new();
     boxes.resize_with(256, Vec::new);
 
     for s in input.split(',').map(|s| s.trim()) {
-        let (label, cmd) = s.split_once(|c| c == '=' || c == '-').unwrap();
+        let (label, cmd) = s
+            .split_once(['=', '-'])
+            .ok_or_else(|| SolveError::parse_at(input, s, "expected '=' or '-'"))?;
         let h = hash(label) as usize;
 
         if cmd.is_empty() {
@@ -34,7 +36,7 @@ fn solve_p2(input: &str) -> usize {
             lenses.remove(label);
         } else {
             // add lens
-            let focal_length = cmd.parse().unwrap();
+            let focal_length = parse_token(input, cmd)?;
             match boxes[h].iter_mut().find(|(lbl, _fl)| lbl == label) {
                 Some((_, fl)) => {
                     *fl = focal_length;
@@ -51,12 +53,12 @@ fn solve_p2(input: &str) -> usize {
         }
     }
 
-    lenses
+    Ok(lenses
         .iter()
         .map(|(lens, (i, fl))| {
             (i + 1) * (boxes[*i].iter().position(|(l, _)| l == lens).unwrap() + 1) * fl
         })
-        .sum()
+        .sum())
 }
 
 fn hash(s: &str) -> i32 {
@@ -1,152 +1,121 @@
 use hashbrown::HashMap;
 
-use crate::Solver;
+use crate::{
+    utils::{
+        geom::{Dir4, DirSet},
+        grid,
+    },
+    SolveError, Solver,
+};
 pub struct Solution;
 impl Solver<usize, usize> for Solution {
-    fn solve(&self, input: &str) -> (usize, usize) {
-        (solve_p1(input), solve_p2(input))
+    fn solve(&self, input: &str) -> Result<(usize, usize), SolveError> {
+        Ok((solve_p1(input)?, solve_p2(input)?))
     }
 }
 
 type RowCol = (i32, i32);
-type Grid = HashMap<RowCol, char>;
-type QueueElem = (RowCol, Dir);
-type EnergizedMap = HashMap<RowCol, Dir>;
-
-type Dir = i32;
-const UP: i32 = 1 << 0;
-const DOWN: i32 = 1 << 1;
-const LEFT: i32 = 1 << 2;
-const RIGHT: i32 = 1 << 3;
+type Grid = grid::Grid<char>;
+type QueueElem = (RowCol, Dir4);
+type EnergizedMap = HashMap<RowCol, DirSet>;
 
 enum MirrorAction {
-    Reflect(Dir),
+    Reflect(Dir4),
     Passthrough,
-    Split(Dir, Dir),
+    Split(Dir4, Dir4),
 }
 
-fn solve_p1(input: &str) -> usize {
-    let (grid, dims) = parse_into_grid(input);
-    project_beam(&dims, ((0, 0), RIGHT), &grid)
+fn solve_p1(input: &str) -> Result<usize, SolveError> {
+    let grid = parse(input)?;
+    project_beam(((0, 0), Dir4::Right), &grid)
 }
 
-fn solve_p2(input: &str) -> usize {
-    let (grid, dims) = parse_into_grid(input);
-    let (rows, cols) = dims;
+fn solve_p2(input: &str) -> Result<usize, SolveError> {
+    let grid = parse(input)?;
+    let (rows, cols) = (grid.rows() as i32, grid.cols() as i32);
 
-    let left_edge = (0..rows).map(|row| ((row, 0), RIGHT));
-    let right_edge = (0..rows).map(|r| ((r, cols - 1), LEFT));
-    let upper_edge = (0..cols).map(|c| ((0, c), DOWN));
-    let bottom_edge = (0..cols).map(|c| ((rows - 1, c), UP));
+    let left_edge = (0..rows).map(|row| ((row, 0), Dir4::Right));
+    let right_edge = (0..rows).map(|r| ((r, cols - 1), Dir4::Left));
+    let upper_edge = (0..cols).map(|c| ((0, c), Dir4::Down));
+    let bottom_edge = (0..cols).map(|c| ((rows - 1, c), Dir4::Up));
 
     left_edge
         .chain(right_edge)
         .chain(bottom_edge)
         .chain(upper_edge)
-        .map(|start| project_beam(&dims, start, &grid))
-        .max()
-        .unwrap()
-}
-
-fn parse_into_grid(input: &str) -> (Grid, (i32, i32)) {
-    let mut grid: Grid = HashMap::new();
-
-    let rows = input.lines().count() as i32;
-    let cols = input.lines().next().unwrap().len() as i32;
-    let dims = (rows, cols);
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            if c == '|' || c == '-' || c == '/' || c == '\\' {
-                grid.insert((row as i32, col as i32), c);
-            }
-        }
-    }
-    (grid, dims)
-}
-
-fn next_pos(pos: &(i32, i32), direction: &Dir) -> (i32, i32) {
-    let (row, col) = pos;
-    match *direction {
-        RIGHT => (*row, col + 1),
-        DOWN => (row + 1, *col),
-        UP => (row - 1, *col),
-        LEFT => (*row, col - 1),
-        _ => unreachable!(),
-    }
+        .try_fold(0, |best, start| Ok(best.max(project_beam(start, &grid)?)))
 }
 
-fn in_cave(pos: &RowCol, dims: &RowCol) -> bool {
-    let (row, col) = pos;
-    let (rows, cols) = dims;
-    *row >= 0 && row < rows && *col >= 0 && col < cols
+fn parse(input: &str) -> Result<Grid, SolveError> {
+    Grid::parse(input, |c| ".|-/\\".contains(c).then_some(c))
 }
 
-fn enqueue_next_pos_if_valid(pos: &RowCol, dir: &Dir, queue: &mut Vec<QueueElem>, dims: &RowCol) {
-    let next_pos = next_pos(pos, dir);
-    if in_cave(&next_pos, dims) {
+fn enqueue_next_pos_if_valid(pos: &RowCol, dir: &Dir4, queue: &mut Vec<QueueElem>, grid: &Grid) {
+    let next_pos = dir.step(*pos);
+    if grid.in_bounds(next_pos) {
         queue.push((next_pos, *dir));
     }
 }
 
 /// Project a beam starting at `pos`
-fn project_beam(dims: &(i32, i32), start: ((i32, i32), i32), grid: &Grid) -> usize {
+fn project_beam(start: QueueElem, grid: &Grid) -> Result<usize, SolveError> {
     let mut q: Vec<QueueElem> = Vec::new();
     let mut energized_map = EnergizedMap::new();
     q.push(start);
 
     while let Some((pos, orig_direction)) = q.pop() {
-        let energy = energized_map.entry(pos).or_insert(0);
-        if *energy & orig_direction != 0 {
+        if !energized_map.entry(pos).or_default().insert(orig_direction) {
             // loop
             continue;
-        } else {
-            *energy = orig_direction
         }
 
-        let dirs: MirrorAction = match (grid.get(&pos), orig_direction) {
+        let dirs: MirrorAction = match (grid.get(pos), orig_direction) {
             // Passing through empty space or splitters
-            (None, _) => MirrorAction::Passthrough,
-            (Some('-'), LEFT) => MirrorAction::Passthrough,
-            (Some('-'), RIGHT) => MirrorAction::Passthrough,
-            (Some('|'), UP) => MirrorAction::Passthrough,
-            (Some('|'), DOWN) => MirrorAction::Passthrough,
+            (Some('.'), _) => MirrorAction::Passthrough,
+            (Some('-'), Dir4::Left) => MirrorAction::Passthrough,
+            (Some('-'), Dir4::Right) => MirrorAction::Passthrough,
+            (Some('|'), Dir4::Up) => MirrorAction::Passthrough,
+            (Some('|'), Dir4::Down) => MirrorAction::Passthrough,
 
             // Splitting the beam
-            (Some('-'), UP) => MirrorAction::Split(LEFT, RIGHT),
-            (Some('-'), DOWN) => MirrorAction::Split(LEFT, RIGHT),
+            (Some('-'), Dir4::Up) => MirrorAction::Split(Dir4::Left, Dir4::Right),
+            (Some('-'), Dir4::Down) => MirrorAction::Split(Dir4::Left, Dir4::Right),
 
-            (Some('|'), LEFT) => MirrorAction::Split(UP, DOWN),
-            (Some('|'), RIGHT) => MirrorAction::Split(UP, DOWN),
+            (Some('|'), Dir4::Left) => MirrorAction::Split(Dir4::Up, Dir4::Down),
+            (Some('|'), Dir4::Right) => MirrorAction::Split(Dir4::Up, Dir4::Down),
 
             // Reflections
-            (Some('\\'), RIGHT) => MirrorAction::Reflect(DOWN),
-            (Some('\\'), UP) => MirrorAction::Reflect(LEFT),
-            (Some('\\'), DOWN) => MirrorAction::Reflect(RIGHT),
-            (Some('\\'), LEFT) => MirrorAction::Reflect(UP),
-            (Some('/'), RIGHT) => MirrorAction::Reflect(UP),
-            (Some('/'), UP) => MirrorAction::Reflect(RIGHT),
-            (Some('/'), DOWN) => MirrorAction::Reflect(LEFT),
-            (Some('/'), LEFT) => MirrorAction::Reflect(DOWN),
-
-            _ => unreachable!(),
+            (Some('\\'), Dir4::Right) => MirrorAction::Reflect(Dir4::Down),
+            (Some('\\'), Dir4::Up) => MirrorAction::Reflect(Dir4::Left),
+            (Some('\\'), Dir4::Down) => MirrorAction::Reflect(Dir4::Right),
+            (Some('\\'), Dir4::Left) => MirrorAction::Reflect(Dir4::Up),
+            (Some('/'), Dir4::Right) => MirrorAction::Reflect(Dir4::Up),
+            (Some('/'), Dir4::Up) => MirrorAction::Reflect(Dir4::Right),
+            (Some('/'), Dir4::Down) => MirrorAction::Reflect(Dir4::Left),
+            (Some('/'), Dir4::Left) => MirrorAction::Reflect(Dir4::Down),
+
+            (tile, _) => {
+                return Err(SolveError::solve(format!(
+                    "unexpected tile {tile:?} at {pos:?}"
+                )))
+            }
         };
 
         // TODO do not insert elements here which we know are outside
         // the grid
         match dirs {
             MirrorAction::Reflect(reflect_dir) => {
-                enqueue_next_pos_if_valid(&pos, &reflect_dir, &mut q, dims);
+                enqueue_next_pos_if_valid(&pos, &reflect_dir, &mut q, grid);
             }
             MirrorAction::Passthrough => {
-                enqueue_next_pos_if_valid(&pos, &orig_direction, &mut q, dims);
+                enqueue_next_pos_if_valid(&pos, &orig_direction, &mut q, grid);
             }
             MirrorAction::Split(dir1, dir2) => {
-                enqueue_next_pos_if_valid(&pos, &dir1, &mut q, dims);
-                enqueue_next_pos_if_valid(&pos, &dir2, &mut q, dims);
+                enqueue_next_pos_if_valid(&pos, &dir1, &mut q, grid);
+                enqueue_next_pos_if_valid(&pos, &dir2, &mut q, grid);
             }
         }
     }
 
-    energized_map.len()
+    Ok(energized_map.len())
 }
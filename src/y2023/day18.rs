@@ -1,69 +1,78 @@
 use itertools::Itertools;
-use nd_vec::vector;
 
-use crate::Solver;
+use crate::{
+    error::parse_token,
+    utils::geom::{Dir4, Point2},
+    SolveError, Solver,
+};
 pub struct Solution;
 impl Solver<i64, i64> for Solution {
-    fn solve(&self, input: &str) -> (i64, i64) {
-        (solve_p1(input), solve_p2(input))
+    fn solve(&self, input: &str) -> Result<(i64, i64), SolveError> {
+        Ok((solve_p1(input)?, solve_p2(input)?))
     }
 }
 
-fn solve_p1(input: &str) -> i64 {
-    let instructions: Vec<(char, _)> = input
+fn solve_p1(input: &str) -> Result<i64, SolveError> {
+    let instructions: Vec<(Dir4, _)> = input
         .lines()
         .map(|line| {
             let (dir, len) = line
-                .split(|c| c == ' ' || c == '(' || c == '#' || c == ')')
+                .split([' ', '(', '#', ')'])
                 .filter(|s| !s.is_empty())
                 .next_tuple()
-                .unwrap();
-            (dir.chars().next().unwrap(), len.parse().unwrap())
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected '<dir> <len>'"))?;
+            let dir = match dir {
+                "R" => Dir4::Right,
+                "D" => Dir4::Down,
+                "L" => Dir4::Left,
+                "U" => Dir4::Up,
+                _ => return Err(SolveError::parse_at(input, dir, "expected R, D, L or U")),
+            };
+            Ok((dir, parse_token(input, len)?))
         })
-        .collect();
+        .collect::<Result<_, SolveError>>()?;
 
-    shoelace(instructions)
+    Ok(shoelace(instructions))
 }
 
-fn solve_p2(input: &str) -> i64 {
-    let instructions: Vec<(char, _)> = input
+fn solve_p2(input: &str) -> Result<i64, SolveError> {
+    let instructions: Vec<(Dir4, _)> = input
         .lines()
         .map(|line| {
-            let (_, hexstr) = line.split_once('#').unwrap();
-            (
-                match hexstr[5..6].chars().next().unwrap() {
-                    '0' => 'R',
-                    '1' => 'D',
-                    '2' => 'L',
-                    '3' => 'U',
-                    _ => unreachable!(),
+            let (_, hexstr) = line
+                .split_once('#')
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected '#'"))?;
+            let (len, dir) = hexstr
+                .get(0..5)
+                .zip(hexstr.get(5..6))
+                .ok_or_else(|| SolveError::parse_at(input, hexstr, "expected six hex digits"))?;
+            Ok((
+                match dir {
+                    "0" => Dir4::Right,
+                    "1" => Dir4::Down,
+                    "2" => Dir4::Left,
+                    "3" => Dir4::Up,
+                    _ => return Err(SolveError::parse_at(input, dir, "expected 0-3")),
                 },
-                i64::from_str_radix(&hexstr[0..5], 16).unwrap(),
-            )
+                i64::from_str_radix(len, 16)
+                    .map_err(|_| SolveError::parse_at(input, len, "expected hex digits"))?,
+            ))
         })
-        .collect();
+        .collect::<Result<_, SolveError>>()?;
 
-    shoelace(instructions)
+    Ok(shoelace(instructions))
 }
 
-fn shoelace(instructions: Vec<(char, i64)>) -> i64 {
-    let mut pos = vector!(0, 0);
+fn shoelace(instructions: Vec<(Dir4, i64)>) -> i64 {
+    let mut pos = Point2::ORIGIN;
     let mut perimeter = 0;
     let mut area = 0;
 
     for (dir, steps) in instructions {
-        let vector = match dir {
-            'R' => vector!(1, 0),
-            'D' => vector!(0, 1),
-            'L' => vector!(-1, 0),
-            'U' => vector!(0, -1),
-            _ => unreachable!(),
-        };
-
-        let scaled_vector = vector * steps;
+        let scaled_vector = dir.offset() * steps;
         pos += scaled_vector;
         perimeter += steps;
-        area += pos.x() * scaled_vector.y();
+        area += pos.x * scaled_vector.y;
     }
 
     area + perimeter / 2 + 1
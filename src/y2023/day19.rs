@@ -1,4 +1,5 @@
 use hashbrown::HashMap;
+use itertools::Itertools;
 use lazy_regex::regex_captures;
 
 #[derive(Debug)]
@@ -8,10 +9,10 @@ enum Rule<'a> {
     Default(&'a str),
 }
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 pub struct Solution;
 impl Solver<usize, usize> for Solution {
-    fn solve(&self, input: &str) -> (usize, usize) {
+    fn solve(&self, input: &str) -> Result<(usize, usize), SolveError> {
         solve(input)
     }
 }
@@ -19,68 +20,100 @@ impl Solver<usize, usize> for Solution {
 type Range = (usize, usize);
 type Ranges = (Range, Range, Range, Range);
 
-fn solve(input: &str) -> (usize, usize) {
-    let (section1, section2) = input.split_once("\n\n").unwrap();
+pub fn solve(input: &str) -> Result<(usize, usize), SolveError> {
+    let (section1, section2) = input
+        .split_once("\n\n")
+        .ok_or_else(|| SolveError::parse_at(input, input, "expected workflows and parts"))?;
 
     let workflows = section1
         .lines()
         .map(|line| {
-            let (_, name, rules) = regex_captures!(r"(\w+)\{(.*)\}", line).unwrap();
+            let (_, name, rules) = regex_captures!(r"(\w+)\{(.*)\}", line)
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected 'name{rules}'"))?;
             let rules = rules
                 .split(',')
                 .map(|rule| {
                     if let Some((_, cat, op, val, dest)) =
-                        regex_captures!(r"(\w)(.)(\d+):(\w+)", rule)
+                        regex_captures!(r"([xmas])([<>])(\d+):(\w+)", rule)
                     {
                         if op == "<" {
-                            Rule::Lt(cat, val.parse().unwrap(), dest)
-                        } else if op == ">" {
-                            Rule::Gt(cat, val.parse().unwrap(), dest)
+                            Ok(Rule::Lt(cat, parse_token(input, val)?, dest))
                         } else {
-                            unreachable!()
+                            Ok(Rule::Gt(cat, parse_token(input, val)?, dest))
                         }
+                    } else if rule.contains(':') {
+                        Err(SolveError::parse_at(input, rule, "invalid rule"))
                     } else {
-                        Rule::Default(rule)
+                        Ok(Rule::Default(rule))
                     }
                 })
-                .collect::<Vec<_>>();
+                .collect::<Result<Vec<_>, _>>()?;
 
-            (name, rules)
+            Ok((name, rules))
         })
-        .collect::<HashMap<_, _>>();
+        .collect::<Result<HashMap<_, _>, SolveError>>()?;
+
+    // Check that all workflows we may be sent to exist. Workflow names are
+    // slices of the input, for the errors when following them.
+    let start = workflows
+        .get_key_value("in")
+        .map(|(name, _)| *name)
+        .ok_or_else(|| SolveError::parse_at(input, section1, "no workflow named 'in'"))?;
+    for rule in workflows.values().flatten() {
+        let (Rule::Lt(_, _, dest) | Rule::Gt(_, _, dest) | Rule::Default(dest)) = rule;
+        if *dest != "A" && *dest != "R" && !workflows.contains_key(dest) {
+            return Err(SolveError::parse_at(input, dest, "unknown workflow"));
+        }
+    }
 
     let parts = section2
         .lines()
         .map(|line| {
-            let (_, x, m, a, s) =
-                regex_captures!(r"\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}", line).unwrap();
-            (
-                x.parse::<usize>().unwrap(),
-                m.parse::<usize>().unwrap(),
-                a.parse::<usize>().unwrap(),
-                s.parse::<usize>().unwrap(),
-            )
+            let (_, x, m, a, s) = regex_captures!(r"\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}", line)
+                .ok_or_else(|| {
+                    SolveError::parse_at(input, line, "expected '{x=..,m=..,a=..,s=..}'")
+                })?;
+            Ok((
+                parse_token::<usize>(input, x)?,
+                parse_token::<usize>(input, m)?,
+                parse_token::<usize>(input, a)?,
+                parse_token::<usize>(input, s)?,
+            ))
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, SolveError>>()?;
 
     let p1 = parts
         .iter()
-        .filter_map(|part| process_workflow("in", part, &workflows))
-        .sum();
+        .map(|part| process_workflow(input, start, part, &workflows))
+        .filter_map_ok(|accepted| accepted)
+        .sum::<Result<_, _>>()?;
 
     let ranges = ((1, 4000), (1, 4000), (1, 4000), (1, 4000));
 
-    let p2 = process_workflow2("in", ranges, &workflows);
+    let p2 = process_workflow2(input, start, ranges, &workflows)?;
+
+    Ok((p1, p2))
+}
 
-    (p1, p2)
+// Looks up a workflow. `name` must be a slice of `input`.
+fn workflow<'a, 'w>(
+    input: &str,
+    name: &str,
+    workflows: &'w HashMap<&str, Vec<Rule<'a>>>,
+) -> Result<&'w [Rule<'a>], SolveError> {
+    workflows
+        .get(name)
+        .map(Vec::as_slice)
+        .ok_or_else(|| SolveError::parse_at(input, name, format!("unknown workflow '{name}'")))
 }
 
 fn process_workflow(
+    input: &str,
     name: &str,
     values: &(usize, usize, usize, usize),
     workflows: &HashMap<&str, Vec<Rule>>,
-) -> Option<usize> {
-    let rules = workflows.get(name).unwrap();
+) -> Result<Option<usize>, SolveError> {
+    let rules = workflow(input, name, workflows)?;
     let (x, m, a, s) = values;
 
     // Apply first matching rule
@@ -98,14 +131,14 @@ fn process_workflow(
             Rule::Default(dest) => Some(dest),
             _ => None,
         })
-        .unwrap();
+        .ok_or_else(|| SolveError::parse_at(input, name, "no rule of the workflow matches"))?;
 
     if *next_workflow == "A" {
-        Some(x + m + a + s)
+        Ok(Some(x + m + a + s))
     } else if *next_workflow == "R" {
-        None
+        Ok(None)
     } else {
-        process_workflow(next_workflow, values, workflows)
+        process_workflow(input, next_workflow, values, workflows)
     }
 }
 
@@ -131,46 +164,70 @@ fn set_cat_range(cat: &str, ranges: Ranges, new_range: Range) -> Ranges {
     }
 }
 
-fn process_workflow2(name: &str, ranges: Ranges, workflows: &HashMap<&str, Vec<Rule>>) -> usize {
+fn process_workflow2(
+    input: &str,
+    name: &str,
+    ranges: Ranges,
+    workflows: &HashMap<&str, Vec<Rule>>,
+) -> Result<usize, SolveError> {
     if name == "A" {
         let ((x0, x1), (m0, m1), (a0, a1), (s0, s1)) = ranges;
         let x = x1 - x0 + 1;
         let m = m1 - m0 + 1;
         let a = a1 - a0 + 1;
         let s = s1 - s0 + 1;
-        return x * m * a * s;
+        return Ok(x * m * a * s);
     } else if name == "R" {
-        return 0;
+        return Ok(0);
     }
 
-    workflows
-        .get(name)
-        .unwrap()
-        .iter()
-        .fold((0, ranges), |(n, ranges), rule| match rule {
-            Rule::Lt(cat, val, dest) => {
-                let (min, max) = get_category_range(cat, ranges);
-                if *val <= min {
-                    (n, ranges)
-                } else {
-                    let true_ranges = set_cat_range(cat, ranges, (min, val - 1));
-                    let false_ranges = set_cat_range(cat, ranges, (*val, max));
-                    let count = process_workflow2(dest, true_ranges, workflows);
-                    (n + count, false_ranges)
+    let (n, _) =
+        workflow(input, name, workflows)?
+            .iter()
+            .try_fold((0, ranges), |(n, ranges), rule| match rule {
+                Rule::Lt(cat, val, dest) => {
+                    let (min, max) = get_category_range(cat, ranges);
+                    if *val <= min {
+                        Ok((n, ranges))
+                    } else {
+                        let true_ranges = set_cat_range(cat, ranges, (min, val - 1));
+                        let false_ranges = set_cat_range(cat, ranges, (*val, max));
+                        let count = process_workflow2(input, dest, true_ranges, workflows)?;
+                        Ok((n + count, false_ranges))
+                    }
                 }
-            }
-            Rule::Gt(cat, val, dest) => {
-                let (min, max) = get_category_range(cat, ranges);
-                if *val >= max {
-                    (n, ranges)
-                } else {
-                    let true_ranges = set_cat_range(cat, ranges, (val + 1, max));
-                    let false_ranges = set_cat_range(cat, ranges, (min, *val));
-                    let count = process_workflow2(dest, true_ranges, workflows);
-                    (n + count, false_ranges)
+                Rule::Gt(cat, val, dest) => {
+                    let (min, max) = get_category_range(cat, ranges);
+                    if *val >= max {
+                        Ok((n, ranges))
+                    } else {
+                        let true_ranges = set_cat_range(cat, ranges, (val + 1, max));
+                        let false_ranges = set_cat_range(cat, ranges, (min, *val));
+                        let count = process_workflow2(input, dest, true_ranges, workflows)?;
+                        Ok((n + count, false_ranges))
+                    }
                 }
-            }
-            Rule::Default(dest) => (n + process_workflow2(dest, ranges, workflows), ranges),
-        })
-        .0
+                Rule::Default(dest) => Ok((
+                    n + process_workflow2(input, dest, ranges, workflows)?,
+                    ranges,
+                )),
+            })?;
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_matching_rule() {
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 1,
+                column: 1,
+                message: "no rule of the workflow matches".to_string()
+            }),
+            solve("in{x<10:A}\n\n{x=20,m=1,a=1,s=1}\n")
+        );
+    }
 }
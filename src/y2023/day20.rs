@@ -2,10 +2,10 @@ use std::collections::VecDeque;
 
 use hashbrown::HashMap;
 
-use crate::Solver;
+use crate::{SolveError, Solver};
 pub struct Solution;
 impl Solver<usize, usize> for Solution {
-    fn solve(&self, input: &str) -> (usize, usize) {
+    fn solve(&self, input: &str) -> Result<(usize, usize), SolveError> {
         solve(input)
     }
 }
@@ -77,28 +77,24 @@ impl Modules {
     fn connect_conjunctions(&mut self) {
         let mut inputs_map: HashMap<String, Vec<String>> = HashMap::new();
 
-        for (name, _mod_type) in &self.module_map {
-            let module = self.module_map.get(name).unwrap();
-            for output in &module.outputs {
-                if output != "output" && output != "rx" {
-                    let output_module = &self.module_map.get(output).unwrap();
-                    if output_module.module_type == ModuleType::Conjuction {
-                        let entry = inputs_map
-                            .entry(output_module.module_name.to_string())
-                            .or_default();
-                        entry.push(name.to_string());
-                    }
+        for (name, module) in &self.module_map {
+            // Outputs were checked when parsing, only "output" and "rx" are
+            // not modules.
+            for output_module in module.outputs.iter().filter_map(|o| self.module_map.get(o)) {
+                if output_module.module_type == ModuleType::Conjuction {
+                    let entry = inputs_map
+                        .entry(output_module.module_name.to_string())
+                        .or_default();
+                    entry.push(name.to_string());
                 }
             }
         }
 
         for (name, inputs) in inputs_map {
-            for input_module in inputs {
-                self.module_map
-                    .get_mut(&name)
-                    .unwrap()
-                    .conj_states
-                    .insert(input_module, false);
+            if let Some(module) = self.module_map.get_mut(&name) {
+                for input_module in inputs {
+                    module.conj_states.insert(input_module, false);
+                }
             }
         }
     }
@@ -143,12 +139,9 @@ impl Modules {
         self.module_map
             .entry(signal.dest.to_string())
             .and_modify(|module| {
-                if module.module_type == Conjuction {
+                if let (Conjuction, Some(source)) = (&module.module_type, &signal.source) {
                     // Track the state of incoming signals
-                    module
-                        .conj_states
-                        .entry(signal.source.unwrap())
-                        .insert(signal.value);
+                    module.conj_states.insert(source.to_string(), signal.value);
                 } else if module.module_type == FlipFlop && signal.value != HIGH {
                     // if a flip-flop receives a high pulse, nothing happens, otherwise
                     // it switches between HIGH/LOW.
@@ -156,7 +149,9 @@ impl Modules {
                 }
             });
 
-        let module = self.module_map.get(&signal.dest).unwrap();
+        let Some(module) = self.module_map.get(&signal.dest) else {
+            return;
+        };
         let output_signal = !module.conj_states.iter().all(|(_, state)| *state);
 
         let out_signals =
@@ -185,32 +180,51 @@ impl Modules {
     }
 }
 
-fn solve(input: &str) -> (usize, usize) {
+pub fn solve(input: &str) -> Result<(usize, usize), SolveError> {
     let mut modules = Modules::new();
 
     for line in input.lines() {
         use ModuleType::*;
-        let mut elems = line
-            .split(|c| c == ' ' || c == ',')
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string());
-        let name: String = elems.next().unwrap();
-        let outputs: Vec<String> = elems.skip(1).collect::<Vec<_>>();
-
-        match name.chars().next().unwrap() {
-            'b' => modules.add_module(name.to_string(), Broadcast, outputs),
-            '%' => modules.add_module(name[1..].to_string(), FlipFlop, outputs),
-            '&' => modules.add_module(name[1..].to_string(), Conjuction, outputs),
-            _ => unreachable!(),
+        let mut elems = line.split([' ', ',']).filter(|s| !s.is_empty());
+        let name = elems
+            .next()
+            .ok_or_else(|| SolveError::parse_at(input, line, "expected a module"))?;
+        let outputs: Vec<String> = elems.skip(1).map(|s| s.to_string()).collect::<Vec<_>>();
+
+        match name.chars().next() {
+            Some('b') if name == "broadcaster" => {
+                modules.add_module(name.to_string(), Broadcast, outputs)
+            }
+            Some('%') => modules.add_module(name[1..].to_string(), FlipFlop, outputs),
+            Some('&') => modules.add_module(name[1..].to_string(), Conjuction, outputs),
+            _ => {
+                return Err(SolveError::parse_at(
+                    input,
+                    name,
+                    "expected 'broadcaster', '%' or '&'",
+                ))
+            }
         };
     }
+
+    // All outputs must be modules, except for the untyped "output" and "rx"
+    if !modules.module_map.contains_key("broadcaster") {
+        return Err(SolveError::parse_at(input, input, "no broadcaster module"));
+    }
+    for line in input.lines() {
+        for output in line.split([' ', ',']).filter(|s| !s.is_empty()).skip(2) {
+            if output != "output" && output != "rx" && !modules.module_map.contains_key(output) {
+                return Err(SolveError::parse_at(input, output, "unknown module"));
+            }
+        }
+    }
     modules.connect_conjunctions();
     let p1 = modules.push_button();
-    let p2 = solve_p2(input);
-    (p1, p2)
+    let p2 = solve_p2(input)?;
+    Ok((p1, p2))
 }
 
-fn solve_p2(input: &str) -> usize {
+fn solve_p2(input: &str) -> Result<usize, SolveError> {
     let mut graph = HashMap::new();
 
     // Clever solution stolen shamelessly from the megathread. Originally
@@ -229,32 +243,32 @@ fn solve_p2(input: &str) -> usize {
         });
 
     let mut res = Vec::new();
-    let initvec = graph.get("broadcaster").unwrap();
+    let initvec = graph
+        .get("broadcaster")
+        .ok_or_else(|| SolveError::solve("no broadcaster module"))?;
 
     for m in initvec {
         let mut m2 = m;
-        let mut bin = String::new();
+        let mut value = 0;
+        let mut bit = 0;
 
         // Decode chains of flip flops as bits in an integer, then
         // compute the lcm of them.
         loop {
             let key = format!("%{m2}");
-            let g = graph.get(&key).unwrap();
+            let g = graph.get(&key).filter(|g| !g.is_empty()).ok_or_else(|| {
+                SolveError::solve(format!("expected '{m2}' to be a flip-flop with outputs"))
+            })?;
             let key0 = format!("%{}", g[0]);
-            bin.insert(
-                0,
-                if g.len() == 2 || !graph.contains_key(&key0) {
-                    '1'
-                } else {
-                    '0'
-                },
-            );
+            if g.len() == 2 || !graph.contains_key(&key0) {
+                value |= 1 << bit;
+            }
+            bit += 1;
 
             // Flip-flops that link to a conjunction are ones everything else is
             // a zero
             let mut nextl = Vec::new();
-            let key1 = format!("%{m2}");
-            for next in graph.get(&key1).unwrap() {
+            for next in g {
                 let next1 = format!("%{next}");
                 if graph.contains_key(&next1) {
                     nextl.push(next);
@@ -268,8 +282,25 @@ fn solve_p2(input: &str) -> usize {
             m2 = nextl[0];
         }
 
-        res.push(usize::from_str_radix(bin.as_str(), 2).unwrap());
+        res.push(value);
     }
 
-    res.iter().fold(1, |a, b| num::integer::lcm(a, *b))
+    Ok(res.iter().fold(1, |a, b| num::integer::lcm(a, *b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_module() {
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 2,
+                column: 7,
+                message: "unknown module".to_string()
+            }),
+            solve("broadcaster -> a\n%a -> b\n")
+        );
+    }
 }
@@ -1,91 +1,123 @@
-use std::collections::VecDeque;
-
-use hashbrown::{HashMap, HashSet};
 use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
 
-use crate::Solver;
+use crate::{
+    utils::{
+        astar::SearchState,
+        grid::{Grid, Pos},
+        search::bfs_layers,
+    },
+    SolveError, StagedSolver,
+};
 pub struct Solution;
-impl Solver<i64, i64> for Solution {
-    fn solve(&self, input: &str) -> (i64, i64) {
-        solve(input)
+impl StagedSolver for Solution {
+    type Parsed = Garden;
+    type P1 = i64;
+    type P2 = i64;
+
+    fn parse(&self, input: &str) -> Result<Garden, SolveError> {
+        parse(input)
+    }
+
+    fn part1(&self, grid: &Garden) -> Result<i64, SolveError> {
+        Ok(solve_p1(grid, 64))
+    }
+
+    fn part2(&self, grid: &Garden) -> Result<i64, SolveError> {
+        Ok(solve_p2(grid))
     }
 }
 
-type RowCol = (i64, i64);
+// The garden map and the start position
+type Garden = (Grid<char>, Pos);
 
-fn solve(input: &str) -> (i64, i64) {
-    let grid = parse(input);
+pub fn solve(input: &str) -> Result<(i64, i64), SolveError> {
+    let grid = parse(input)?;
     let p1 = solve_p1(&grid, 64);
     let p2 = solve_p2(&grid);
-    (p1, p2)
+    Ok((p1, p2))
 }
 
-fn parse(input: &str) -> (HashMap<RowCol, char>, RowCol, RowCol) {
-    let mut grid: HashMap<RowCol, char> = HashMap::new();
-    let mut start: Option<RowCol> = None;
-
-    let rows = input.lines().count() as i64;
-    let cols = input.lines().next().unwrap().len() as i64;
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            let pos = (row as i64, col as i64);
-            match c {
-                'S' => {
-                    start = Some(pos);
-                    grid.insert(pos, '.');
-                }
-                c if c == '#' || c == '.' => {
-                    grid.insert(pos, c);
-                }
-                _ => {}
-            }
-        }
+fn parse(input: &str) -> Result<Garden, SolveError> {
+    let grid = Grid::parse(input, |c| "S#.".contains(c).then_some(c))?;
+    if grid.rows() != grid.cols() {
+        return Err(SolveError::parse_at(
+            input,
+            input,
+            "the garden must be square",
+        ));
     }
-
-    (grid, start.unwrap(), (rows, cols))
+    let start = grid
+        .find(&'S')
+        .ok_or_else(|| SolveError::parse_at(input, input, "no start position ('S')"))?;
+    Ok((grid, start))
 }
 
-fn solve_p1(grid: &(HashMap<RowCol, char>, RowCol, RowCol), max_depth: i64) -> i64 {
+fn solve_p1(grid: &Garden, max_depth: i64) -> i64 {
     bfs(grid, max_depth)
 }
 
-fn bfs(grid: &(HashMap<RowCol, char>, RowCol, RowCol), max_depth: i64) -> i64 {
-    let (map, start, (rows, cols)) = grid;
-    assert_eq!(rows, cols);
+// A position in the garden, which repeats infinitely in all directions.
+struct Step<'a> {
+    garden: &'a Garden,
+    pos: Pos,
+}
 
-    let mut queue: VecDeque<(i64, RowCol)> = VecDeque::new();
-    let mut visited: HashSet<RowCol> = HashSet::new();
-    let mut total = 0;
-    let parity = max_depth % 2;
-    queue.push_back((0, *start));
-
-    while let Some((depth, pos)) = queue.pop_front() {
-        if depth > max_depth {
-            break;
-        } else if visited.contains(&pos) {
-            continue;
-        } else if depth % 2 == parity {
-            total += 1;
-        }
+impl<'a> SearchState for Step<'a> {
+    type Key = Pos;
+    type Iter = std::vec::IntoIter<Step<'a>>;
 
-        visited.insert(pos);
+    fn key(&self) -> Pos {
+        self.pos
+    }
+
+    fn is_goal(&self) -> bool {
+        false
+    }
+
+    fn cost(&self) -> usize {
+        0
+    }
+
+    fn heuristic(&self) -> usize {
+        0
+    }
 
-        let (row, col) = pos;
+    fn next_states(self) -> Self::Iter {
+        let (map, _) = self.garden;
+        let (row, col) = self.pos;
         [
             (row - 1, col),
             (row, col - 1),
             (row, col + 1),
             (row + 1, col),
         ]
-        .iter()
-        .filter(|(r, c)| {
-            let p = (r.rem_euclid(*rows), c.rem_euclid(*cols));
-            !visited.contains(&(*r, *c)) && map.get(&p).unwrap() != &'#'
+        .into_iter()
+        .filter(|&pos| *map.get_wrapping(pos) != '#')
+        .map(|pos| Step {
+            garden: self.garden,
+            pos,
         })
-        .for_each(|&nbr| queue.push_back((depth + 1, nbr)));
+        .collect::<Vec<_>>()
+        .into_iter()
     }
+}
+
+fn bfs(grid: &Garden, max_depth: i64) -> i64 {
+    let (_, start) = grid;
 
+    // Plots reached in an even number of steps can be reached again at any
+    // later even number of steps, by stepping back and forth.
+    let mut total = 0;
+    let parity = max_depth as usize % 2;
+    let start = Step {
+        garden: grid,
+        pos: *start,
+    };
+    bfs_layers(start, max_depth as usize, |depth, _| {
+        if depth % 2 == parity {
+            total += 1;
+        }
+    });
     total
 }
 
@@ -94,9 +126,8 @@ fn bfs(grid: &(HashMap<RowCol, char>, RowCol, RowCol), max_depth: i64) -> i64 {
 //
 // Inspired by
 // https://github.com/mebeim/aoc/blob/master/2023/original_solutions/day21.py
-fn solve_p2(grid: &(HashMap<RowCol, char>, RowCol, RowCol)) -> i64 {
-    let (_map, _start, (rows, cols)) = grid;
-    assert_eq!(rows, cols);
+fn solve_p2(grid: &Garden) -> i64 {
+    let rows = grid.0.rows() as i64;
 
     let constant = 26501365;
     let modulo = constant % rows;
@@ -116,6 +147,6 @@ fn solve_p2(grid: &(HashMap<RowCol, char>, RowCol, RowCol)) -> i64 {
     let d1 = v[1] - v[0];
     let a0 = ((v[2] - v[1]) - d1) >> 1;
     let b0 = d1 - 3 * a0;
-    let x = constant.div_ceil(*rows);
+    let x = constant.div_ceil(rows);
     a0 * (x * x) + b0 * x + (v[0] - b0 - a0)
 }
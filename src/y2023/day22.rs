@@ -1,6 +1,6 @@
 use std::fmt;
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 
 use itertools::Itertools;
 
@@ -191,21 +191,23 @@ mod tests {
 }
 
 /// Create a new tower by parsing the puzzle input
-fn new_from_input(input: &str) -> Vec<Brick> {
+fn new_from_input(input: &str) -> Result<Vec<Brick>, SolveError> {
     input
         .lines()
         .zip(0..)
         .map(|(line, i)| {
             let (x0, y0, z0, x1, y1, z1) = line
                 .split(|c| "~,".contains(c))
-                .map(|s| s.parse::<CoordInt>().unwrap())
+                .map(|s| parse_token::<CoordInt>(input, s))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
                 .collect_tuple()
-                .unwrap();
-            Brick::new(
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected 'x,y,z~x,y,z'"))?;
+            Ok(Brick::new(
                 i,
                 Coord::new_from_tuple(&(x0, y0, z0)),
                 Coord::new_from_tuple(&(x1, y1, z1)),
-            )
+            ))
         })
         .collect::<_>()
 }
@@ -287,19 +289,19 @@ fn find_removable_bricks(tower: &Tower) -> Vec<i32> {
 
 pub struct Solution;
 impl Solver<ResultType, ResultType> for Solution {
-    fn solve(&self, input: &str) -> (ResultType, ResultType) {
+    fn solve(&self, input: &str) -> Result<(ResultType, ResultType), SolveError> {
         solve(input)
     }
 }
 
-fn solve(input: &str) -> (ResultType, ResultType) {
-    let mut tower = new_from_input(input);
+pub fn solve(input: &str) -> Result<(ResultType, ResultType), SolveError> {
+    let mut tower = new_from_input(input)?;
     drop_all_bricks(&mut tower);
     let removables: Vec<i32> = find_removable_bricks(&tower);
-    (
+    Ok((
         tower.len() - removables.len(),
         removables.iter().sum::<i32>() as usize,
-    )
+    ))
 }
 
 #[test]
@@ -311,5 +313,5 @@ fn test_ex1() {
 2,0,5~2,2,5
 0,1,6~2,1,6
 1,1,8~1,1,9";
-    assert_eq!((5, 7), solve(ex1));
+    assert_eq!(Ok((5, 7)), solve(ex1));
 }
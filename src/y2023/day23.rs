@@ -5,11 +5,11 @@ use hashbrown::HashMap;
 
 use par_dfs::sync::{FastDfs, FastNode};
 
-use crate::Solver;
+use crate::{utils::grid, SolveError, Solver};
 
 type RowCol = (i32, i32);
 type ResultType = i64;
-type Grid = HashMap<RowCol, char>;
+type Grid = grid::Grid<char>;
 
 #[derive(Clone, Derivative)]
 #[derivative(Hash, Eq, PartialEq)]
@@ -37,7 +37,7 @@ impl<'a> FastNode for Node<'a> {
         let (row, col) = self.pos;
         let grid = self.grid;
 
-        if let Some(downhills_nbr) = match *self.grid.get(&self.pos).unwrap_or(&'#') {
+        if let Some(downhills_nbr) = match *self.grid.get(self.pos).unwrap_or(&'#') {
             '<' => Some(Node {
                 pos: (row, col - 1),
                 grid,
@@ -71,7 +71,7 @@ impl<'a> FastNode for Node<'a> {
                 ]
                 .iter()
                 .filter_map(|nbr| {
-                    let c = *self.grid.get(nbr).unwrap_or(&'#');
+                    let c = *self.grid.get(*nbr).unwrap_or(&'#');
                     if c == '#' {
                         None
                     } else {
@@ -91,29 +91,21 @@ impl<'a> FastNode for Node<'a> {
 
 pub struct Solution;
 impl Solver<ResultType, ResultType> for Solution {
-    fn solve(&self, input: &str) -> (ResultType, ResultType) {
+    fn solve(&self, input: &str) -> Result<(ResultType, ResultType), SolveError> {
         solve(input)
     }
 }
 
-fn solve(input: &str) -> (ResultType, ResultType) {
-    let mut grid: Grid = HashMap::new();
+pub fn solve(input: &str) -> Result<(ResultType, ResultType), SolveError> {
+    let grid: Grid = input.parse()?;
+    let rows = grid.rows() as i32;
     let mut start: RowCol = (0, 0);
 
-    let rows = input.lines().count() as i32;
-    let _cols = input.lines().next().unwrap().len() as i32;
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            grid.insert((row as i32, col as i32), c);
-        }
-    }
-
-    for ((row, col), c) in &grid {
-        if *row == rows - 1 && *c == '.' {
-            // _end = (*row, *col)
-        } else if *row == 0 && *c == '.' {
-            start = (*row, *col)
+    for ((row, col), c) in grid.iter() {
+        if row == rows - 1 && *c == '.' {
+            // _end = (row, col)
+        } else if row == 0 && *c == '.' {
+            start = (row, col)
         }
     }
 
@@ -128,6 +120,9 @@ fn solve(input: &str) -> (ResultType, ResultType) {
         depth: 0,
     };
 
+    // The hike is the longest path which does not visit a tile twice. The
+    // searches in utils::search find cheapest paths or visit each state
+    // once, so this keeps its own depth-first walk.
     let dfs = FastDfs::<Node>::new(root, None, false);
     // println!("collecting...");
 
@@ -161,7 +156,7 @@ fn solve(input: &str) -> (ResultType, ResultType) {
     let p1 = nodes.values().map(|node| node.depth).max().unwrap() as i64;
 
     // println!("p1={p1}");
-    (p1, 0)
+    Ok((p1, 0))
 }
 
 // #[test]
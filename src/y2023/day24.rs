@@ -1,6 +1,6 @@
 use itertools::Itertools;
 
-use crate::Solver;
+use crate::{error::parse_token, SolveError, Solver};
 
 // extern crate geo;
 // extern crate line_intersection;
@@ -54,37 +54,41 @@ impl Hailstone {
 }
 
 impl Solver<ResultType, ResultType> for Solution {
-    fn solve(&self, input: &str) -> (ResultType, ResultType) {
+    fn solve(&self, input: &str) -> Result<(ResultType, ResultType), SolveError> {
         solve(input)
     }
 }
 
-fn solve(input: &str) -> (ResultType, ResultType) {
-    let hailstones = parse(input);
+pub fn solve(input: &str) -> Result<(ResultType, ResultType), SolveError> {
+    let hailstones = parse(input)?;
     let p1 = solve_p1(&hailstones, (200000000000000f64, 400000000000000f64));
-    (p1, 0)
+    Ok((p1, 0))
 }
 
-fn parse(input: &str) -> Vec<Hailstone> {
+fn parse(input: &str) -> Result<Vec<Hailstone>, SolveError> {
     input
         .lines()
         .map(|line| {
             let (x, y, z, dx, dy, dz) = line
                 .split(|s| ", @".contains(s))
                 .filter(|s| !s.is_empty())
-                .map(|n| n.parse().unwrap())
+                .map(|n| parse_token(input, n))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
                 .collect_tuple()
-                .unwrap();
-            Hailstone {
+                .ok_or_else(|| {
+                    SolveError::parse_at(input, line, "expected 'x, y, z @ dx, dy, dz'")
+                })?;
+            Ok(Hailstone {
                 x,
                 y,
                 z,
                 dx,
                 dy,
                 dz,
-            }
+            })
         })
-        .collect_vec()
+        .collect()
 }
 
 fn solve_p1(hailstones: &[Hailstone], test_area: (f64, f64)) -> usize {
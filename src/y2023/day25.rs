@@ -1,10 +1,11 @@
-use std::collections::VecDeque;
-
-use hashbrown::{HashMap, HashSet};
+use hashbrown::HashMap;
 use itertools::Itertools;
 use petgraph::{graph::UnGraph, stable_graph::NodeIndex};
 
-use crate::Solver;
+use crate::{
+    utils::{astar::SearchState, search::bfs_layers},
+    SolveError, Solver,
+};
 
 pub struct Solution;
 
@@ -17,86 +18,126 @@ struct Data {
 }
 
 impl Data {
-    fn new_from_input(input: &str) -> Self {
+    fn new_from_input(input: &str) -> Result<Self, SolveError> {
         let mut graph = GraphType::new_undirected();
-        let mut map = HashMap::new();
-
-        let lines = input
-            .lines()
-            .map(|line| {
-                line.split(|c| ": ".contains(c))
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect_vec()
-            })
-            .collect_vec();
-
-        // Create the nodes
-        for line in &lines {
-            for e in line {
-                if !map.contains_key(e) {
-                    let idx = graph.add_node(e.to_string());
-                    map.insert(e.to_string(), idx);
-                }
-            }
-        }
-
-        // Add the edges
-        for line in &lines {
-            let lhs = map.get(&line[0]).unwrap();
-            for e in &line[1..] {
-                let rhs = map.get(e).unwrap();
-                graph.add_edge(*lhs, *rhs, String::new());
+        let mut map: HashMap<String, NodeIndex> = HashMap::new();
+
+        for line in input.lines() {
+            let (lhs, rhs) = line
+                .split_once(": ")
+                .filter(|(lhs, rhs)| !lhs.is_empty() && !rhs.trim().is_empty())
+                .ok_or_else(|| SolveError::parse_at(input, line, "expected 'name: name ...'"))?;
+            let lhs = node(&mut graph, &mut map, lhs);
+            for rhs in rhs.split(' ').filter(|s| !s.is_empty()) {
+                let rhs = node(&mut graph, &mut map, rhs);
+                graph.add_edge(lhs, rhs, String::new());
             }
         }
-        Data { graph, map }
+        Ok(Data { graph, map })
     }
 
-    fn remove_edge(&mut self, a: &str, b: &str) {
-        let a_idx = self.map.get(&a.to_string()).unwrap();
-        let b_idx = self.map.get(&b.to_string()).unwrap();
-        let edge_idx = self.graph.find_edge(*a_idx, *b_idx).unwrap();
+    fn remove_edge(&mut self, a: &str, b: &str) -> Result<(), SolveError> {
+        let edge_idx = self
+            .map
+            .get(a)
+            .zip(self.map.get(b))
+            .and_then(|(a_idx, b_idx)| self.graph.find_edge(*a_idx, *b_idx))
+            .ok_or_else(|| SolveError::solve(format!("no edge between {a} and {b}")))?;
         self.graph.remove_edge(edge_idx);
+        Ok(())
     }
 }
 
+// The index of a node, which is added if it is new
+fn node(graph: &mut GraphType, map: &mut HashMap<String, NodeIndex>, name: &str) -> NodeIndex {
+    *map.entry(name.to_string())
+        .or_insert_with(|| graph.add_node(name.to_string()))
+}
+
 impl Solver<ResultType, ResultType> for Solution {
-    fn solve(&self, input: &str) -> (ResultType, ResultType) {
+    fn solve(&self, input: &str) -> Result<(ResultType, ResultType), SolveError> {
         solve(input)
     }
 }
 
-fn solve(input: &str) -> (ResultType, ResultType) {
-    let mut data = Data::new_from_input(input);
-    let p1 = solve_p1(&mut data);
-    (p1, 0)
+pub fn solve(input: &str) -> Result<(ResultType, ResultType), SolveError> {
+    let mut data = Data::new_from_input(input)?;
+    let p1 = solve_p1(&mut data)?;
+    Ok((p1, 0))
 }
 
-fn solve_p1(data: &mut Data) -> usize {
+fn solve_p1(data: &mut Data) -> Result<usize, SolveError> {
     // These were found by visual inspection of the graph (plotted using
     // Dot::new)
 
-    data.remove_edge("rxt", "bqq");
-    data.remove_edge("qxr", "btp");
-    data.remove_edge("vfx", "bgl");
+    data.remove_edge("rxt", "bqq")?;
+    data.remove_edge("qxr", "btp")?;
+    data.remove_edge("vfx", "bgl")?;
 
     let total_size = data.graph.node_count();
+    let start = Component {
+        graph: &data.graph,
+        node: data
+            .graph
+            .node_indices()
+            .next()
+            .ok_or_else(|| SolveError::solve("the graph is empty"))?,
+    };
+    let size_set1 = bfs_layers(start, usize::MAX, |_, _| {});
+    Ok(size_set1 * (total_size - size_set1))
+}
 
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back(data.graph.node_indices().next().unwrap());
+// A node of the graph, for finding the nodes connected to it.
+struct Component<'a> {
+    graph: &'a GraphType,
+    node: NodeIndex,
+}
 
-    while let Some(node) = queue.pop_front() {
-        if visited.contains(&node) {
-            continue;
-        }
-        visited.insert(node);
+impl<'a> SearchState for Component<'a> {
+    type Key = NodeIndex;
+    type Iter = std::vec::IntoIter<Component<'a>>;
 
-        for nbr in data.graph.neighbors(node) {
-            queue.push_back(nbr);
-        }
+    fn key(&self) -> NodeIndex {
+        self.node
     }
 
-    let size_set1 = visited.len();
-    size_set1 * (total_size - size_set1)
+    fn is_goal(&self) -> bool {
+        false
+    }
+
+    fn cost(&self) -> usize {
+        0
+    }
+
+    fn heuristic(&self) -> usize {
+        0
+    }
+
+    fn next_states(self) -> Self::Iter {
+        self.graph
+            .neighbors(self.node)
+            .map(|node| Component {
+                graph: self.graph,
+                node,
+            })
+            .collect_vec()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bad_line() {
+        assert_eq!(
+            Err(SolveError::Parse {
+                line: 2,
+                column: 1,
+                message: "expected 'name: name ...'".to_string()
+            }),
+            solve("jqt: rhn xhk\nrhn\n")
+        );
+    }
 }